@@ -0,0 +1,42 @@
+#import bevy_sprite::mesh2d_vertex_output MeshVertexOutput
+
+@group(1) @binding(0)
+var screen_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var screen_sampler: sampler;
+@group(1) @binding(2)
+var lut_texture: texture_2d<f32>;
+@group(1) @binding(3)
+var lut_sampler: sampler;
+
+// The lookup texture is a 16x16x16 color cube in sRGB, blue slices laid out horizontally
+const LUT_SIZE: f32 = 16.0;
+
+fn to_srgb(c: vec3<f32>) -> vec3<f32> {
+    return select(1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, c * 12.92, c <= vec3(0.0031308));
+}
+
+fn to_linear(c: vec3<f32>) -> vec3<f32> {
+    return select(pow((c + 0.055) / 1.055, vec3(2.4)), c / 12.92, c <= vec3(0.04045));
+}
+
+fn lut(rg: vec2<f32>, slice: f32) -> vec3<f32> {
+    let uv = vec2(
+        (slice * LUT_SIZE + rg.x * (LUT_SIZE - 1.0) + 0.5) / (LUT_SIZE * LUT_SIZE),
+        (rg.y * (LUT_SIZE - 1.0) + 0.5) / LUT_SIZE,
+    );
+    return textureSample(lut_texture, lut_sampler, uv).rgb;
+}
+
+@fragment
+fn fragment(mesh: MeshVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(screen_texture, screen_sampler, mesh.uv);
+    let c = to_srgb(clamp(color.rgb, vec3(0.0), vec3(1.0)));
+
+    // Red and green are filtered by the sampler, blue between the two nearest slices
+    let b = c.b * (LUT_SIZE - 1.0);
+    let slice = floor(b);
+    let remapped = mix(lut(c.rg, slice), lut(c.rg, min(slice + 1.0, LUT_SIZE - 1.0)), b - slice);
+
+    return vec4(to_linear(remapped), color.a);
+}
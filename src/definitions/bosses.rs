@@ -0,0 +1,110 @@
+use lazy_static::lazy_static;
+
+use crate::definitions::cutscenes;
+use crate::entities::boss::{BossAttack, BossDef, BossPhase};
+use crate::entities::pickup::Pickup;
+use crate::logic::Flags;
+use crate::music::BGM;
+use crate::params;
+
+lazy_static! {
+    pub static ref BOSS_1: BossDef = BossDef {
+        name: "Cave Guardian",
+        id: 1,
+        hp: 4,
+        start: None,
+        intro: Flags::Boss1Start,
+        defeated: Flags::Boss1Defeated,
+        phases: vec![
+            BossPhase {
+                until_hp: Some(3),
+                attacks: vec![BossAttack::Guard],
+                ..Default::default()
+            },
+            BossPhase {
+                until_hp: Some(2),
+                attacks: vec![BossAttack::Stunned(params::BOSS_STUN_DELAY)],
+                ..Default::default()
+            },
+            BossPhase {
+                until_hp: Some(0),
+                attacks: vec![BossAttack::Guard],
+                ..Default::default()
+            },
+        ],
+        end: Some(&*cutscenes::BOSS_1_END),
+        loot: Some((Pickup::FullHeart, 0.0, params::BOSS_EYES_Y.2)),
+    };
+
+    pub static ref BOSS_2: BossDef = BossDef {
+        name: "Forest Keeper",
+        id: 2,
+        hp: 8,
+        start: Some(Flags::Boss2Start),
+        intro: Flags::Boss2Start,
+        defeated: Flags::Boss2Defeated,
+        phases: vec![
+            BossPhase {
+                until_hp: Some(5),
+                attacks: vec![BossAttack::Guard],
+                ..Default::default()
+            },
+            BossPhase {
+                until_hp: Some(2),
+                attacks: vec![BossAttack::Slam],
+                bgm: Some(BGM::ForestBoss),
+                ..Default::default()
+            },
+            BossPhase {
+                until_hp: Some(0),
+                attacks: vec![BossAttack::Charge],
+                ..Default::default()
+            },
+        ],
+        end: Some(&*cutscenes::BOSS_2_END),
+        loot: None,
+    };
+
+    pub static ref BOSS_3: BossDef = BossDef {
+        name: "The Old Man",
+        id: 3,
+        hp: 12,
+        start: Some(Flags::Boss3Start),
+        intro: Flags::Boss3Start,
+        defeated: Flags::Boss3Defeated,
+        phases: vec![
+            BossPhase {
+                attacks: vec![BossAttack::Wait(params::BOSS3_TRANSFORM_TIME)],
+                repeat: false,
+                ..Default::default()
+            },
+            BossPhase {
+                attacks: vec![BossAttack::Levitate, BossAttack::Land],
+                repeat: false,
+                minions: vec![("ZombieL", -32., 32.), ("ZombieL", 32., 32.)],
+                ..Default::default()
+            },
+            BossPhase {
+                until_hp: Some(6),
+                attacks: vec![BossAttack::Jump],
+                flags: vec![Flags::SizeS],
+                ..Default::default()
+            },
+            BossPhase {
+                until_hp: Some(0),
+                attacks: vec![BossAttack::Center, BossAttack::Sweep],
+                flags: vec![Flags::SizeL],
+                ..Default::default()
+            },
+        ],
+        end: Some(&*cutscenes::OUTRO),
+        loot: None,
+    };
+
+    /// Bosses chained by the boss rush, with the spawner the player starts from
+    pub static ref BOSS_RUSH: Vec<(&'static BossDef, &'static str)> = vec![
+        (&*BOSS_1, "z1_boss"),
+        (&*BOSS_2, "z2_save"),
+        (&*BOSS_3, "z1_end"),
+    ];
+}
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::entities::player::PlayerSize;
+use crate::logic::AttackDir;
 
 fn rectangle(offset: Vec2, size: Vec2) -> Collider {
     Collider::compound(vec![(
@@ -35,12 +36,18 @@ pub fn bird() -> Collider {
     rectangle(vec2(0.0, 5.0), vec2(18.0, 6.0))
 }
 
-pub fn sword_collider(player_size: &PlayerSize, flip: bool) -> Collider {
+pub fn sword_collider(player_size: &PlayerSize, flip: bool, dir: AttackDir) -> Collider {
     let flip_x = if flip { -1.0 } else { 1.0 };
-    let (offset, size) = match player_size {
-        PlayerSize::S => (vec2(7.0 * flip_x, 7.5), vec2(8.0, 3.0)),
-        PlayerSize::M => (vec2(14.0 * flip_x, 10.5), vec2(12.0, 5.0)),
-        PlayerSize::L => (vec2(25.5 * flip_x, 19.5), vec2(21.0, 5.0)),
+    let (offset, size) = match (dir, player_size) {
+        (AttackDir::Side, PlayerSize::S) => (vec2(7.0 * flip_x, 7.5), vec2(8.0, 3.0)),
+        (AttackDir::Side, PlayerSize::M) => (vec2(14.0 * flip_x, 10.5), vec2(12.0, 5.0)),
+        (AttackDir::Side, PlayerSize::L) => (vec2(25.5 * flip_x, 19.5), vec2(21.0, 5.0)),
+        (AttackDir::Up, PlayerSize::S) => (vec2(0.0, 13.0), vec2(6.0, 8.0)),
+        (AttackDir::Up, PlayerSize::M) => (vec2(0.0, 22.0), vec2(10.0, 12.0)),
+        (AttackDir::Up, PlayerSize::L) => (vec2(0.0, 42.5), vec2(16.0, 21.0)),
+        (AttackDir::Down, PlayerSize::S) => (vec2(0.0, -3.0), vec2(6.0, 8.0)),
+        (AttackDir::Down, PlayerSize::M) => (vec2(0.0, -5.0), vec2(10.0, 12.0)),
+        (AttackDir::Down, PlayerSize::L) => (vec2(0.0, -9.5), vec2(16.0, 21.0)),
     };
 
     rectangle(offset, size)
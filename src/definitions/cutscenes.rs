@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 
 use lazy_static::lazy_static;
 
+use crate::graphics::TransitionEffect;
 use crate::logic::{CSEvent, Flags};
 use crate::music::BGM;
 use crate::params;
@@ -103,6 +104,11 @@ lazy_static! {
         CSEvent::BGM(BGM::CavesBoss),
     ]);
 
+    pub static ref BOSS_1_END: VecDeque<CSEvent> = VecDeque::from([
+        CSEvent::RemoveFlag(Flags::Boss1WallPresent),
+        CSEvent::BGM(BGM::Caves),
+    ]);
+
     pub static ref BOSS_2: VecDeque<CSEvent> = VecDeque::from([
         CSEvent::AddFlag(Flags::Boss2Start),
         CSEvent::AddFlag(Flags::Boss2WallPresent),
@@ -141,12 +147,12 @@ lazy_static! {
         CSEvent::ToggleCinema(false),
         CSEvent::SetRelativeTime(0.25),
         CSEvent::Wait(0.4),
-        CSEvent::fade_out_with_speed(8.0),
+        CSEvent::cover_with_duration(TransitionEffect::Iris, params::DEATH_TRANSITION_TIME),
         CSEvent::SetLife(params::STARTING_LIFE),
         CSEvent::Wait(1.0),
         CSEvent::SetRelativeTime(1.0),
         CSEvent::Reload,
-        CSEvent::fade_in_with_speed(2.0),
+        CSEvent::reveal(TransitionEffect::Iris),
     ]);
 
     pub static ref SWORD_1: VecDeque<CSEvent> = VecDeque::from([
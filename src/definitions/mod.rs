@@ -1,2 +1,3 @@
+pub mod bosses;
 pub mod colliders;
-pub mod cutscenes;
\ No newline at end of file
+pub mod cutscenes;
@@ -5,6 +5,7 @@ use bevy::sprite::TextureAtlasSprite;
 use crate::{logic, params, util};
 use crate::entities::{animation, EntityID, player};
 use crate::entities::player::PlayerSize;
+use crate::entities::registry::entity_def;
 use crate::music::SFX;
 
 pub type Index = usize;
@@ -55,6 +56,8 @@ pub enum AnimStep {
     Dash,
     Land,
     Attack,
+    /// Telegraphed enemy attack
+    Lunge,
 }
 
 impl AnimStep {
@@ -163,14 +166,9 @@ fn get_index_for_sequence(
 
 impl EntityID {
     fn get_rule(&self, step: &AnimStep) -> AnimationRule {
-        match self {
-            EntityID::Player(size) => get_player_rule(step, size),
-            EntityID::Zombie(_) | EntityID::OldGuy => get_zombie_rule(step),
-            EntityID::Bird(_) => get_bird_rule(step),
-            EntityID::Boss1 => get_boss_1_rule(step),
-            EntityID::Boss2 => get_boss_2_rule(step),
-            EntityID::Boss3 => get_boss_3_rule(step),
-            _ => AnimationRule::Missing,
+        match entity_def(self.identifier()).and_then(|def| def.animation) {
+            Some(rule) => rule(self, step),
+            None => AnimationRule::Missing,
         }
     }
 }
@@ -233,10 +231,53 @@ pub fn get_zombie_rule(state: &AnimStep) -> AnimationRule {
             SeqPart::Frame(1),
             SeqPart::Wait(0.75),
         ]),
+        // Same shuffle as idle, only quicker
+        AnimStep::Walk => AnimationRule::Loop(vec![
+            SeqPart::Frame(0),
+            SeqPart::Wait(0.25),
+            SeqPart::Frame(1),
+            SeqPart::Wait(0.25),
+        ]),
+        AnimStep::Lunge => AnimationRule::Sequence(vec![
+            SeqPart::Frame(2),
+            SeqPart::Wait(params::ZOMBIE_LUNGE_WINDUP / 4.0),
+            SeqPart::Frame(3),
+            SeqPart::Wait(params::ZOMBIE_LUNGE_WINDUP / 4.0),
+            SeqPart::Frame(2),
+            SeqPart::Wait(params::ZOMBIE_LUNGE_WINDUP / 4.0),
+            SeqPart::Frame(3),
+            SeqPart::Wait(params::ZOMBIE_LUNGE_WINDUP / 4.0),
+            SeqPart::Frame(4),
+        ]),
         _ => AnimationRule::Missing,
     }
 }
 
+pub fn get_shooter_rule(state: &AnimStep) -> AnimationRule {
+    match state {
+        AnimStep::Attack => AnimationRule::Loop(vec![
+            SeqPart::Frame(2),
+            SeqPart::Wait(0.08),
+            SeqPart::Frame(3),
+            SeqPart::Wait(0.08),
+        ]),
+        _ => get_zombie_rule(state),
+    }
+}
+
+/// The large zombie sheet only has two frames, it shakes before lunging
+pub fn get_zombie_l_rule(state: &AnimStep) -> AnimationRule {
+    match state {
+        AnimStep::Lunge => AnimationRule::Loop(vec![
+            SeqPart::Frame(0),
+            SeqPart::Wait(0.05),
+            SeqPart::Frame(1),
+            SeqPart::Wait(0.05),
+        ]),
+        _ => get_zombie_rule(state),
+    }
+}
+
 pub fn get_bird_rule(state: &AnimStep) -> AnimationRule {
     match state {
         AnimStep::Idle => AnimationRule::Still(0),
@@ -0,0 +1,304 @@
+use std::collections::VecDeque;
+
+use bevy::math::vec3;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_rapier2d::geometry::Collider;
+
+use crate::entities::animation::AnimStep;
+use crate::entities::NamedEntity;
+use crate::entities::pickup::{Pickup, spawn_pickup};
+use crate::entities::registry::entity_def;
+use crate::graphics::particles::BossKilled;
+use crate::logic::{BossRush, CSEvent, Cutscene, Flags, GameData, Hitbox};
+use crate::music::{BGM, PlayBGMEvent};
+use crate::params;
+use crate::screens::Textures;
+
+/// Attack performed by a boss, interpreted by the boss' own update system.
+///
+/// [BossAttack::Wait] is handled by [update_bosses], other attacks end when
+/// the boss calls [BossFight::next_attack].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BossAttack {
+    /// Do nothing for the given amount of seconds
+    Wait(f32),
+    /// Expose weak points until they are hit
+    Guard,
+    /// Weak for the given amount of seconds, then the fight restarts
+    Stunned(f32),
+    /// Hit the ground, damage zones follow the animation
+    Slam,
+    /// Strike toward the player after each hit
+    Charge,
+    /// Hover while the minions fight
+    Levitate,
+    /// Fall back to the ground
+    Land,
+    /// Jump toward the player, then rest
+    Jump,
+    /// Go back to the center of the arena
+    Center,
+    /// Sweep across the arena
+    Sweep,
+}
+
+pub struct BossPhase {
+    /// The phase ends when the boss HP drops to this value, [None] if it is ended by its attacks
+    pub until_hp: Option<u8>,
+    pub attacks: Vec<BossAttack>,
+    /// Loop the attacks, otherwise the phase ends after the last one
+    pub repeat: bool,
+    /// Played when the phase begins
+    pub cutscene: Option<&'static VecDeque<CSEvent>>,
+    pub bgm: Option<BGM>,
+    /// Set when the phase begins
+    pub flags: Vec<Flags>,
+    /// Spawned when the phase begins: registry identifier and offset from the boss
+    pub minions: Vec<(&'static str, f32, f32)>,
+}
+
+impl Default for BossPhase {
+    fn default() -> Self {
+        BossPhase {
+            until_hp: None,
+            attacks: vec![],
+            repeat: true,
+            cutscene: None,
+            bgm: None,
+            flags: vec![],
+            minions: vec![],
+        }
+    }
+}
+
+pub struct BossDef {
+    pub name: &'static str,
+    /// Id of the [crate::graphics::particles::Boss] emitters
+    pub id: u8,
+    pub hp: u8,
+    /// The fight begins with this flag, or as soon as the boss is spawned
+    pub start: Option<Flags>,
+    /// Set when the fight is introduced, shows the health bar
+    pub intro: Flags,
+    pub defeated: Flags,
+    pub phases: Vec<BossPhase>,
+    /// Played when the boss is defeated
+    pub end: Option<&'static VecDeque<CSEvent>>,
+    /// Dropped after the death sequence: pickup and offset from the boss
+    pub loot: Option<(Pickup, f32, f32)>,
+}
+
+impl BossDef {
+    /// Name of the loot in [GameData::removed_named] once collected
+    fn loot_name(&self) -> String {
+        format!("boss{}_loot", self.id)
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct BossFight {
+    pub def: &'static BossDef,
+    pub hp: u8,
+    phase: Option<usize>,
+    attack: usize,
+    /// Time spent in the current attack
+    pub timer: f32,
+    phase_done: bool,
+    /// Defeat already handled, the game data may be reset by the boss rush
+    killed: bool,
+    loot_dropped: bool,
+}
+
+impl BossFight {
+    pub fn new(def: &'static BossDef) -> Self {
+        BossFight {
+            def,
+            hp: def.hp,
+            phase: None,
+            attack: 0,
+            timer: 0.0,
+            phase_done: false,
+            killed: false,
+            loot_dropped: false,
+        }
+    }
+
+    /// [None] until the fight begins
+    pub fn phase(&self) -> Option<usize> {
+        self.phase
+    }
+
+    pub fn attack(&self) -> Option<BossAttack> {
+        let phase = &self.def.phases[self.phase?];
+        phase.attacks.get(self.attack).copied()
+    }
+
+    pub fn next_attack(&mut self) {
+        let Some(phase) = self.phase else { return };
+        let attacks = self.def.phases[phase].attacks.len();
+        self.timer = 0.0;
+        self.attack += 1;
+        if self.attack >= attacks {
+            if self.def.phases[phase].repeat {
+                self.attack = 0;
+            } else {
+                self.attack = attacks.saturating_sub(1);
+                self.phase_done = true;
+            }
+        }
+    }
+
+    /// A single hit never goes past the end of the current phase
+    pub fn hit(&mut self, damage: usize) {
+        let floor = self.phase.and_then(|phase| self.def.phases[phase].until_hp).unwrap_or(0);
+        let hp = self.hp.saturating_sub(damage.min(u8::MAX as usize) as u8);
+        self.hp = if self.hp > floor { hp.max(floor) } else { hp };
+    }
+
+    pub fn dead(&self) -> bool {
+        self.hp == 0
+    }
+
+    /// Restart the fight from the first phase, with full HP
+    pub fn reset(&mut self) {
+        *self = BossFight::new(self.def);
+    }
+
+    fn phase_over(&self) -> bool {
+        let Some(phase) = self.phase else { return false };
+        if phase + 1 >= self.def.phases.len() { return false; }
+        self.phase_done || self.def.phases[phase].until_hp.map_or(false, |hp| self.hp <= hp)
+    }
+
+    fn enter_phase(&mut self, phase: usize) {
+        self.phase = Some(phase);
+        self.attack = 0;
+        self.timer = 0.0;
+        self.phase_done = false;
+    }
+}
+
+/// Sent when a boss enters a new phase
+#[derive(Event)]
+pub struct BossPhaseEvent {
+    pub boss: Entity,
+    pub phase: usize,
+}
+
+/// Boss children, their colliders are removed when the boss is defeated
+#[derive(Component)]
+pub struct BossPart;
+
+/// Entity spawned by a boss
+#[derive(Component)]
+pub struct BossMinion(pub Entity);
+
+fn start_phase(
+    commands: &mut Commands,
+    textures: &Textures,
+    data: &mut GameData,
+    bgm: &mut EventWriter<PlayBGMEvent>,
+    boss: Entity,
+    pos: Vec3,
+    phase: &BossPhase,
+) {
+    if let Some(cutscene) = phase.cutscene {
+        commands.insert_resource(Cutscene::from(cutscene));
+    }
+    if let Some(music) = phase.bgm {
+        bgm.send(PlayBGMEvent(music));
+    }
+    for flag in &phase.flags {
+        data.set_flag(*flag);
+    }
+
+    for &(identifier, dx, dy) in &phase.minions {
+        let Some(def) = entity_def(identifier) else {
+            error!("Unknown minion: {}", identifier);
+            continue;
+        };
+        let mut minion = commands.spawn(SpriteSheetBundle {
+            texture_atlas: def.atlas.map(|atlas| atlas(textures)).unwrap_or_default(),
+            transform: Transform::from_xyz(pos.x + dx, pos.y + dy, params::z_pos::PLAYER),
+            ..default()
+        });
+        minion
+            .insert(BossMinion(boss))
+            .insert(Hitbox);
+        if let Some(id) = def.id { minion.insert(id(&EntityInstance::default())); }
+        if let Some(enemy) = def.enemy { minion.insert(enemy); }
+        if let Some(collider) = def.collider { minion.insert(collider()); }
+        if let Some(health) = def.health { minion.insert(health); }
+        if let Some(spawn) = def.spawn { spawn(&mut minion); }
+    }
+}
+
+pub fn update_bosses(
+    mut commands: Commands,
+    textures: Res<Textures>,
+    mut bosses: Query<(Entity, &mut BossFight, &Transform, Option<&mut AnimStep>)>,
+    parts: Query<(Entity, &Parent), (With<BossPart>, With<Collider>)>,
+    mut data: ResMut<GameData>,
+    mut bgm: EventWriter<PlayBGMEvent>,
+    mut phase_events: EventWriter<BossPhaseEvent>,
+    boss_rush: Option<Res<BossRush>>,
+    boss_killed: Option<Res<BossKilled>>,
+    time: Res<Time>,
+) {
+    for (e, mut fight, pos, step) in bosses.iter_mut() {
+        let def = fight.def;
+
+        // Boss killed
+        if fight.dead() || data.has_flag(def.defeated) {
+            if !fight.killed && !data.has_flag(def.defeated) {
+                data.set_flag(def.defeated);
+                commands.insert_resource(BossKilled::new(def.id));
+                // The boss rush chains the fights instead
+                if let (Some(cutscene), None) = (def.end, &boss_rush) {
+                    commands.insert_resource(Cutscene::from(cutscene));
+                }
+            }
+            fight.killed = true;
+
+            // Loot is dropped once the death sequence is over
+            if let (Some((pickup, dx, dy)), None, None) = (def.loot, &boss_rush, &boss_killed) {
+                let name = def.loot_name();
+                if !fight.loot_dropped && !data.removed_named.contains(&name) {
+                    fight.loot_dropped = true;
+                    let loot = spawn_pickup(&mut commands, &textures, pickup, pos.translation + vec3(dx, dy, 0.0));
+                    commands.entity(loot).insert(NamedEntity(name));
+                }
+            }
+            fight.hp = 0;
+            if let Some(mut step) = step { step.set_if_neq(AnimStep::Fall); }
+            // Remove colliders
+            commands.entity(e).remove::<Collider>();
+            for (part, parent) in parts.iter() {
+                if parent.get() == e { commands.entity(part).remove::<Collider>(); }
+            }
+            continue;
+        }
+
+        // Fight start
+        if fight.phase().is_none() {
+            if !def.start.map_or(true, |flag| data.has_flag(flag)) { continue; }
+            fight.enter_phase(0);
+            start_phase(&mut commands, &textures, &mut data, &mut bgm, e, pos.translation, &def.phases[0]);
+            phase_events.send(BossPhaseEvent { boss: e, phase: 0 });
+        }
+
+        fight.timer += time.delta_seconds();
+        if let Some(BossAttack::Wait(duration)) = fight.attack() {
+            if fight.timer >= duration { fight.next_attack(); }
+        }
+
+        // Phase transitions, the boss may skip a phase
+        while fight.phase_over() {
+            let phase = fight.phase().unwrap_or(0) + 1;
+            fight.enter_phase(phase);
+            start_phase(&mut commands, &textures, &mut data, &mut bgm, e, pos.translation, &def.phases[phase]);
+            phase_events.send(BossPhaseEvent { boss: e, phase });
+        }
+    }
+}
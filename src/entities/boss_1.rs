@@ -1,38 +1,18 @@
+use bevy::math::vec2;
 use bevy::prelude::*;
-use bevy::utils::petgraph::matrix_graph::Zero;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::geometry::Collider;
 use bevy_rapier2d::prelude::RigidBody;
 
-use crate::definitions::colliders;
+use crate::definitions::{bosses, colliders};
 use crate::entities::animation::AnimStep;
+use crate::entities::boss::{BossAttack, BossFight, BossPart};
 use crate::entities::common::get_enemy;
-use crate::graphics::Hurt;
-use crate::graphics::particles::{Boss, BossKilled};
-use crate::logic::{ColliderBundle, Damaged, Flags, GameData, Hitbox};
-use crate::music::{BGM, PlayBGMEvent};
+use crate::graphics::particles::Boss;
+use crate::logic::{ColliderBundle, Flags, GameData, Health, Hitbox, HitStop, Wounded};
 use crate::params;
 use crate::screens::Textures;
 
-#[derive(Component, Clone)]
-pub struct Boss1State {
-    hp: u8,
-    right_eye: u8,
-    left_eye: u8,
-    stun: f32,
-}
-
-impl Default for Boss1State {
-    fn default() -> Self {
-        Boss1State {
-            hp: 4,
-            right_eye: 2,
-            left_eye: 2,
-            stun: 0.0,
-        }
-    }
-}
-
 #[derive(Clone, Default, Bundle, LdtkEntity)]
 pub struct Boss1Bundle {
     #[worldly]
@@ -42,20 +22,23 @@ pub struct Boss1Bundle {
     #[from_entity_instance]
     pub collider_bundle: ColliderBundle,
     boss1: Boss1,
-    state: Boss1State,
 }
 
 #[derive(Component, Clone, Default)]
 pub struct Boss1;
 
-#[derive(Component)]
-pub struct Boss1Part;
-
+/// The boss loses one HP when an eye's [Health] is emptied, the other eye opens again
 #[derive(Component)]
 pub struct Boss1Eye {
     left: bool,
 }
 
+/// Eye falling off the boss after its death
+#[derive(Component)]
+pub struct DetachedEye {
+    velocity: Vec2,
+}
+
 pub fn init(
     mut commands: Commands,
     textures: Res<Textures>,
@@ -63,15 +46,18 @@ pub fn init(
     data: Res<GameData>,
 ) {
     let Ok(e) = boss.get_single() else { return; };
+    let dead = data.has_flag(Flags::Boss1Defeated);
+
+    let mut fight = BossFight::new(&bosses::BOSS_1);
+    if dead { fight.hp = 0; }
+
     commands
         .entity(e)
         .insert(get_enemy("Boss1").expect("Couldn't find enemy"))
         .insert(Boss(1))
-        .insert(Boss1Part)
+        .insert(fight)
     ;
 
-    let dead = data.has_flag(Flags::Boss1Defeated);
-
     commands.entity(e).with_children(|builder| {
         for (dx, left) in [
             (-params::BOSS_EYES_DX, true),
@@ -94,12 +80,13 @@ pub fn init(
                 })
                 .insert(Boss1Eye { left })
                 .insert(get_enemy("Eye1").expect("Couldn't find enemy"))
+                .insert(Health::new(params::BOSS1_EYE_HP))
                 .insert(ColliderBundle {
                     collider: colliders::eye_1_collider(),
                     rigid_body: RigidBody::Fixed,
                     ..default()
                 })
-                .insert(Boss1Part)
+                .insert(BossPart)
                 .insert(Hitbox)
             ;
         }
@@ -108,63 +95,43 @@ pub fn init(
 
 pub fn update(
     mut commands: Commands,
-    mut boss: Query<(&mut Boss1State, &mut Collider, &mut AnimStep), With<Boss1>>,
-    mut damage: EventReader<Damaged>,
-    mut eyes: Query<(Entity, &Boss1Eye, &mut TextureAtlasSprite, &mut Transform), Without<Boss1>>,
-    mut data: ResMut<GameData>,
-    parts: Query<Entity, With<Boss1Part>>,
-    mut time: ResMut<Time>,
-    mut bgm: EventWriter<PlayBGMEvent>,
+    mut boss: Query<(&mut BossFight, &mut Collider, &mut AnimStep), With<Boss1>>,
+    mut wounded: EventReader<Wounded>,
+    mut eyes: Query<(Entity, &Boss1Eye, &mut Health, &mut TextureAtlasSprite, &mut Transform), Without<Boss1>>,
+    mut hit_stop: ResMut<HitStop>,
 ) {
-    let Ok((mut state, mut collider, mut step)) = boss.get_single_mut() else { return; };
+    let Ok((mut fight, mut collider, mut step)) = boss.get_single_mut() else { return; };
+    if fight.dead() { return; }
 
-    let old_hp = state.hp;
+    let old_hp = fight.hp;
 
-    if state.hp == 3 && !state.stun.is_zero() {
-        state.stun -= time.delta_seconds();
-        if state.stun.is_sign_negative() {
-            *state = Boss1State::default();
+    // The fight restarts if the boss isn't hit while stunned
+    if let Some(BossAttack::Stunned(duration)) = fight.attack() {
+        if fight.timer >= duration {
+            fight.reset();
+            eyes.for_each_mut(|(_, _, mut health, _, _)| health.heal());
         }
     }
 
     // Damage
-    for Damaged{ entity: e, .. } in damage.iter() {
-        let Ok((eye_e, eye, _, _)) = eyes.get_mut(*e) else { continue };
-        match eye.left {
-            true => {
-                if state.left_eye > 0 {
-                    state.left_eye -= 1;
-                    commands.entity(eye_e).insert(Hurt::new(params::ENEMY_HURT_TIME));
-                    if state.left_eye == 0 {
-                        if state.hp > 0 { state.hp -= 1; } else { /* TODO: KILL ANIM */ }
-                        if state.hp == 3 { state.stun = params::BOSS_STUN_DELAY; }
-                        if state.hp > 0 { state.right_eye = 2; }
-                    }
-                }
-            }
-            false => {
-                if state.right_eye > 0 {
-                    state.right_eye -= 1;
-                    commands.entity(eye_e).insert(Hurt::new(params::ENEMY_HURT_TIME));
-                    if state.right_eye == 0 {
-                        if state.hp > 0 { state.hp -= 1; } else { /* TODO: KILL ANIM */ }
-                        if state.hp == 3 { state.stun = params::BOSS_STUN_DELAY; }
-                        if state.hp > 0 { state.left_eye = 2; }
-                    }
-                }
+    for Wounded { entity: e, .. } in wounded.iter() {
+        let Ok((_, eye, health, _, _)) = eyes.get(*e) else { continue };
+        if !health.dead() { continue; }
+        let closed = eye.left;
+        fight.hit(1);
+        if !fight.dead() {
+            for (_, other, mut health, _, _) in eyes.iter_mut() {
+                if other.left != closed { health.heal(); }
             }
         }
     }
 
-    let dead = state.hp == 0 || data.has_flag(Flags::Boss1Defeated);
+    let dead = fight.dead();
 
-    for ((_, eye, mut eye_sprite, mut pos)) in eyes.iter_mut() {
-        eye_sprite.index = match eye.left {
-            true => if state.left_eye > 0 && !dead { 0 } else { 1 }
-            false => if state.right_eye > 0 && !dead  { 0 } else { 1 }
-        };
-        if old_hp != state.hp {
-            match state.hp {
+    for (_, eye, health, mut eye_sprite, mut pos) in eyes.iter_mut() {
+        eye_sprite.index = if health.hp > 0 && !dead { 0 } else { 1 };
+        if old_hp != fight.hp {
+            match fight.hp {
                 4 => pos.translation.y = params::BOSS_EYES_Y.0,
                 3 => pos.translation.y = params::BOSS_EYES_Y.1,
                 2 => {
@@ -176,30 +143,47 @@ pub fn update(
         }
     }
 
-    // Boss killed
+    // Death sequence, the explosion and the loot are handled by [update_bosses]
     if dead {
-        // Kill animation
-        if !data.has_flag(Flags::Boss1Defeated) {
-            data.set_flag(Flags::Boss1Defeated);
-            data.remove_flag(Flags::Boss1WallPresent);
-            commands.insert_resource(BossKilled::new(1));
-            bgm.send(PlayBGMEvent(BGM::Caves));
-        }
-        step.set_if_neq(AnimStep::Fall);
-        // Remove colliders
-        parts.for_each(|p_e| { commands.entity(p_e).remove::<Collider>(); });
-    } else {
-        // Boss HP updated
-        if old_hp != state.hp {
-            // Update collider
-            *collider = colliders::boss1(state.hp);
-
-            step.set_if_neq(match state.hp {
-                3 => AnimStep::Jump,
-                2 | 1 => AnimStep::Prejump,
-                0 => AnimStep::Fall,
-                _ => AnimStep::Idle,
+        hit_stop.time_left = params::BOSS_KILL_HIT_STOP;
+        for (eye_e, eye, _, _, _) in eyes.iter() {
+            let dir = if eye.left { -1.0 } else { 1.0 };
+            commands.entity(eye_e).insert(DetachedEye {
+                velocity: vec2(dir * params::BOSS1_EYE_DETACH_SPEED.0, params::BOSS1_EYE_DETACH_SPEED.1),
             });
         }
     }
-}
\ No newline at end of file
+
+    // Boss HP updated
+    if !dead && old_hp != fight.hp {
+        // Update collider
+        *collider = colliders::boss1(fight.hp);
+
+        step.set_if_neq(match fight.hp {
+            3 => AnimStep::Jump,
+            2 | 1 => AnimStep::Prejump,
+            _ => AnimStep::Idle,
+        });
+    }
+}
+
+pub fn detach_eyes(
+    mut eyes: Query<(&mut DetachedEye, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (mut eye, mut pos) in eyes.iter_mut() {
+        if eye.velocity == Vec2::ZERO { continue; }
+
+        // Landed
+        if pos.translation.y <= params::BOSS_EYES_Y.2 && eye.velocity.y < 0.0 {
+            pos.translation.y = params::BOSS_EYES_Y.2;
+            pos.rotation = Quat::IDENTITY;
+            eye.velocity = Vec2::ZERO;
+            continue;
+        }
+
+        eye.velocity.y -= params::GRAVITY * time.delta_seconds();
+        pos.translation += (eye.velocity * time.delta_seconds()).extend(0.0);
+        pos.rotate_z(eye.velocity.x.signum() * params::BOSS1_EYE_SPIN * time.delta_seconds());
+    }
+}
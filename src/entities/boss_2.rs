@@ -5,31 +5,19 @@ use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::geometry::Collider;
 use bevy_rapier2d::prelude::RigidBody;
 
-use crate::definitions::{colliders, cutscenes};
+use crate::definitions::{bosses, colliders};
 use crate::entities::animation::{AnimationEvent, AnimStep};
+use crate::entities::boss::{BossAttack, BossFight, BossPart, BossPhaseEvent};
 use crate::entities::common::get_enemy;
 use crate::entities::damage_zone::DamageZone;
 use crate::entities::player::Player;
 use crate::graphics::Hurt;
-use crate::graphics::particles::{Boss, BossKilled};
-use crate::logic::{ColliderBundle, Cutscene, Damaged, Flags, GameData, Hitbox};
-use crate::music::{BGM, PlayBGMEvent, PlaySFXEvent, SFX};
+use crate::graphics::particles::Boss;
+use crate::logic::{ColliderBundle, Health, Hitbox, Wounded};
+use crate::music::{PlaySFXEvent, SFX};
 use crate::params;
 use crate::screens::{ScreenShake, Textures};
 
-#[derive(Component, Clone)]
-pub struct Boss2State {
-    hp: u8,
-}
-
-impl Default for Boss2State {
-    fn default() -> Self {
-        Boss2State {
-            hp: 8,
-        }
-    }
-}
-
 #[derive(Clone, Default, Bundle, LdtkEntity)]
 pub struct Boss2Bundle {
     #[worldly]
@@ -39,15 +27,11 @@ pub struct Boss2Bundle {
     #[from_entity_instance]
     pub collider_bundle: ColliderBundle,
     boss1: Boss2,
-    state: Boss2State,
 }
 
 #[derive(Component, Clone, Default)]
 pub struct Boss2;
 
-#[derive(Component)]
-pub struct Boss2Part;
-
 #[derive(Component)]
 pub struct Boss2Eye;
 
@@ -60,7 +44,7 @@ pub fn init(
         .entity(e)
         .insert(get_enemy("Boss2").expect("Couldn't find enemy"))
         .insert(Boss(2))
-        .insert(Boss2Part)
+        .insert(BossFight::new(&bosses::BOSS_2))
         .insert(Hitbox)
     ;
 }
@@ -68,77 +52,78 @@ pub fn init(
 pub fn update(
     mut commands: Commands,
     textures: Res<Textures>,
-    mut boss: Query<(Entity, &mut Boss2State, &mut Collider, &TextureAtlasSprite, &mut AnimStep), With<Boss2>>,
-    mut damage: EventReader<Damaged>,
+    mut boss: Query<(Entity, &mut BossFight, &mut Collider, &TextureAtlasSprite, &mut AnimStep, Option<&mut Health>), With<Boss2>>,
+    mut wounded: EventReader<Wounded>,
+    mut phases: EventReader<BossPhaseEvent>,
     mut eye: Query<(Entity, &mut TextureAtlasSprite, &mut Transform, &GlobalTransform), (With<Boss2Eye>, Without<Boss2>)>,
-    mut data: ResMut<GameData>,
+    mut eye_health: Query<&mut Health, (With<Boss2Eye>, Without<Boss2>)>,
     player: Query<&Transform, (Without<Boss2Eye>, Without<Boss2>, With<Player>)>,
-    parts: Query<Entity, With<Boss2Part>>,
     mut events: EventReader<AnimationEvent>,
-    mut damage_zone: Query<&mut Collider, (With<Boss2Part>, With<DamageZone>, Without<Boss2>)>,
-    mut bgm: EventWriter<PlayBGMEvent>,
+    mut damage_zone: Query<&mut Collider, (With<BossPart>, With<DamageZone>, Without<Boss2>)>,
     mut sfx: EventWriter<PlaySFXEvent>,
 ) {
-    let Ok((boss_e, mut state, mut collider, sprite, mut step)) = boss.get_single_mut() else { return; };
+    let Ok((boss_e, mut fight, mut collider, sprite, mut step, health)) = boss.get_single_mut() else { return; };
     let Ok(player_pos) = player.get_single() else { return };
 
-    let old_hp = state.hp;
+    let old_hp = fight.hp;
+    let mut strike = false;
 
-    // Damage
-    for Damaged{ entity: e, .. } in damage.iter() {
-
-        // Initial state
-        if *e == boss_e && state.hp >= 6 {
-            state.hp -= 1;
-
-            if state.hp > 5 {
-                commands.entity(boss_e).insert(Hurt::new(params::ENEMY_HURT_TIME));
-            } else if state.hp == 5 {
-                // Switch to phase 2
-                commands.remove_resource::<ScreenShake>();
-                commands.insert_resource(ScreenShake::new(params::BOSS2_SHAKE));
-                bgm.send(PlayBGMEvent(BGM::ForestBoss));
-                sfx.send(PlaySFXEvent(SFX::BossOut));
-                commands.entity(boss_e).insert(Hurt::new_with_shake(params::ENEMY_HURT_TIME, params::BOSS2_SHAKE));
-                step.set_if_neq(AnimStep::Walk);
-
-                // Spawn eye
-                commands.entity(boss_e).with_children(|builder| {
-                    builder
-                        .spawn(SpriteSheetBundle {
-                            texture_atlas: textures.boss_2_eye.clone(),
-                            transform: Transform::from_xyz(0.0, 0.0, 1.0),
-                            ..default()
-                        })
-                        .insert(Boss2Part)
-                        .insert(Boss2Eye)
-                        .insert(get_enemy("Eye2").expect("Couldn't find enemy"))
-                        .insert(ColliderBundle {
-                            collider: colliders::eye_2_collider(),
-                            rigid_body: RigidBody::Fixed,
-                            ..default()
-                        })
-                        .insert(Hitbox)
-                    ;
-                });
-            }
+    // Only the body can be hit during the first phase, then the eye
+    let guard = fight.attack() == Some(BossAttack::Guard);
+    match (&health, guard) {
+        (None, true) => { commands.entity(boss_e).insert(Health::new(fight.hp as usize)); }
+        (Some(_), false) => { commands.entity(boss_e).remove::<Health>(); }
+        _ => {}
+    }
 
+    // Damage
+    for Wounded{ entity: e, damage, .. } in wounded.iter() {
+        if fight.dead() { break; }
+        if *e == boss_e {
+            fight.hit(*damage);
+        } else if eye_health.contains(*e) {
+            fight.hit(*damage);
+            strike |= fight.attack() == Some(BossAttack::Charge);
         }
+    }
 
-        if let Ok((_, eye_sprite, _, eye_pos)) = eye.get(*e) {
-            if state.hp > 0 && state.hp <= 5 {
-                commands.entity(*e).insert(Hurt::new(params::ENEMY_HURT_TIME));
-                state.hp -= 1;
-
-                if state.hp > 0 && state.hp <= 2 {
-                    if player_pos.translation.x < eye_pos.translation().x {
-                        step.set_if_neq(AnimStep::Dash);
-                    } else {
-                        step.set_if_neq(AnimStep::Attack);
-                    }
-                }
-            }
-        }
+    // The health of the body and the eye mirrors the boss HP
+    let hp = fight.hp as usize;
+    if let Some(mut health) = health { if health.hp != hp { health.hp = hp; } }
+    for mut health in eye_health.iter_mut() { if health.hp != hp { health.hp = hp; } }
+
+    for BossPhaseEvent { boss, phase } in phases.iter() {
+        if *boss != boss_e { continue; }
+        if *phase == 2 { strike = true; }
+        if *phase != 1 { continue; }
+
+        // Switch to phase 2
+        commands.remove_resource::<ScreenShake>();
+        commands.insert_resource(ScreenShake::new(params::BOSS2_SHAKE));
+        sfx.send(PlaySFXEvent(SFX::BossOut));
+        commands.entity(boss_e).insert(Hurt::new_with_shake(params::ENEMY_HURT_TIME, params::BOSS2_SHAKE));
+        step.set_if_neq(AnimStep::Walk);
+
+        // Spawn eye
+        commands.entity(boss_e).with_children(|builder| {
+            builder
+                .spawn(SpriteSheetBundle {
+                    texture_atlas: textures.boss_2_eye.clone(),
+                    transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                    ..default()
+                })
+                .insert(BossPart)
+                .insert(Boss2Eye)
+                .insert(get_enemy("Eye2").expect("Couldn't find enemy"))
+                .insert(Health::new(fight.hp as usize))
+                .insert(ColliderBundle {
+                    collider: colliders::eye_2_collider(),
+                    rigid_body: RigidBody::Fixed,
+                    ..default()
+                })
+                .insert(Hitbox)
+            ;
+        });
     }
 
     // Damage zone apparition
@@ -161,7 +146,7 @@ pub fn update(
                                     .insert(TransformBundle::from_transform(Transform::from_xyz(0.0, 6.0, 0.0)))
                                     .insert(Hitbox)
                                     .insert(get_enemy("DamageZone").expect("Couldn't spawn DamageZone"))
-                                    .insert(Boss2Part)
+                                    .insert(BossPart)
                                 ;
                             })
                         ;
@@ -177,9 +162,20 @@ pub fn update(
         }
     }
 
-    let dead = state.hp == 0 || data.has_flag(Flags::Boss2Defeated);
+    let dead = fight.dead();
+
+    // Strike toward the player after each hit
+    if strike && !dead {
+        if let Ok((_, _, _, eye_pos)) = eye.get_single() {
+            if player_pos.translation.x < eye_pos.translation().x {
+                step.set_if_neq(AnimStep::Dash);
+            } else {
+                step.set_if_neq(AnimStep::Attack);
+            }
+        }
+    }
 
-    if let Ok((eye_e, mut eye_sprite, mut eye_pos, _)) = eye.get_single_mut() {
+    if let Ok((_, mut eye_sprite, mut eye_pos, _)) = eye.get_single_mut() {
         let index = if dead { 1 } else { sprite.index };
         let (x, y, flip) = match index {
             1 => (15.0, 16.0, true),
@@ -195,22 +191,9 @@ pub fn update(
         eye_pos.rotation = if flip { Quat::from_axis_angle(Vec3::new(0., 0., 1.), PI / 2.0) } else { Quat::IDENTITY };
     };
 
-    // Boss killed
-    if dead {
-        // Kill animation
-        if !data.has_flag(Flags::Boss2Defeated) {
-            data.set_flag(Flags::Boss2Defeated);
-            commands.insert_resource(BossKilled::new(2));
-            commands.insert_resource(Cutscene::from(&cutscenes::BOSS_2_END));
-        }
-        step.set_if_neq(AnimStep::Fall);
-        // Remove colliders
-        parts.for_each(|p_e| { commands.entity(p_e).remove::<Collider>(); });
-    } else {
-        // Boss HP updated
-        if old_hp != state.hp {
-            // Update collider
-            *collider = colliders::boss2(state.hp);
-        }
+    // Boss HP updated, the kill animation is handled by [update_bosses]
+    if !dead && old_hp != fight.hp {
+        // Update collider
+        *collider = colliders::boss2(fight.hp);
     }
-}
\ No newline at end of file
+}
@@ -3,57 +3,24 @@ use std::f32::consts::PI;
 use bevy::math::vec2;
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
-use bevy_rapier2d::dynamics::{LockedAxes, RigidBody};
-use bevy_rapier2d::geometry::Collider;
 use bevy_rapier2d::plugin::RapierContext;
 
-use crate::definitions::{colliders, cutscenes};
-use crate::entities::{Enemy, EntityID};
-use crate::entities::animation::{AnimationEvent, AnimStep};
+use crate::definitions::bosses;
+use crate::entities::Enemy;
+use crate::entities::animation::AnimStep;
+use crate::entities::boss::{BossAttack, BossFight, BossMinion, BossPhaseEvent};
 use crate::entities::common::get_enemy;
 use crate::entities::player::{Player, PlayerHitEvent};
-use crate::entities::zombie::Zombie;
-use crate::graphics::Hurt;
-use crate::graphics::particles::{Boss, BossKilled};
-use crate::logic::{ColliderBundle, Cutscene, Damaged, Flags, GameData, Hitbox};
+use crate::graphics::particles::Boss;
+use crate::logic::{ColliderBundle, Health, Hitbox, Wounded};
 use crate::params;
-use crate::screens::Textures;
 
-#[derive(Component, Clone)]
+/// Movement of the boss, the fight itself is a [BossFight]
+#[derive(Component, Clone, Default)]
 pub struct Boss3State {
-    hp: u8,
-    step: Boss3Step,
     jump: bool,
-    x_target: f32,
     x_speed: f32,
-    left: bool,
-    timer: f32,
-}
-
-#[derive(Clone)]
-enum Boss3Step {
-    Sleep,
-    Transform,
-    Step1,
-    BeforeStep2,
-    Step2,
-    AfterJump,
-    Step3,
-    BeforeDash,
-}
-
-impl Default for Boss3State {
-    fn default() -> Self {
-        Boss3State {
-            hp: 12,
-            step: Boss3Step::Sleep,
-            jump: false,
-            x_target: 0.0,
-            x_speed: 0.0,
-            left: false,
-            timer: 0.0,
-        }
-    }
+    sweep: f32,
 }
 
 #[derive(Clone, Default, Bundle, LdtkEntity)]
@@ -71,9 +38,6 @@ pub struct Boss3Bundle {
 #[derive(Component, Clone, Default)]
 pub struct Boss3;
 
-#[derive(Component)]
-pub struct Boss3Invoc;
-
 pub fn init(
     mut commands: Commands,
     boss: Query<Entity, Added<Boss3>>,
@@ -83,6 +47,7 @@ pub fn init(
         .entity(e)
         .insert(get_enemy("Boss3").expect("Couldn't find enemy"))
         .insert(Boss(3))
+        .insert(BossFight::new(&bosses::BOSS_3))
         .insert(Hitbox)
     ;
 }
@@ -106,100 +71,64 @@ pub fn hit_player(
 
 pub fn update(
     mut commands: Commands,
-    textures: Res<Textures>,
-    mut boss: Query<(Entity, &mut Boss3State, &mut Collider, &mut Transform, &mut AnimStep), With<Boss3>>,
-    mut damage: EventReader<Damaged>,
-    mut data: ResMut<GameData>,
+    mut boss: Query<(Entity, &mut Boss3State, &mut BossFight, &mut Transform, &mut AnimStep, Option<&mut Health>), With<Boss3>>,
+    mut wounded: EventReader<Wounded>,
+    mut phases: EventReader<BossPhaseEvent>,
     player: Query<&Transform, (Without<Boss3>, With<Player>)>,
     time: Res<Time>,
-    invocations: Query<&Boss3Invoc>,
-    mut events: EventReader<AnimationEvent>,
+    minions: Query<&BossMinion>,
 ) {
-    if !data.has_flag(Flags::Boss3Start) { return; }
-    let Ok((boss_e, mut state, mut collider, mut boss_pos, mut step)) = boss.get_single_mut() else { return; };
+    let Ok((boss_e, mut state, mut fight, mut boss_pos, mut step, health)) = boss.get_single_mut() else { return; };
     let Ok(player_pos) = player.get_single() else { return };
+    if fight.dead() { return; }
+
+    // Only hurt while jumping or sweeping
+    let vulnerable = matches!(fight.attack(), Some(BossAttack::Jump | BossAttack::Sweep));
+    match (&health, vulnerable) {
+        (None, true) => { commands.entity(boss_e).insert(Health::new(fight.hp as usize)); }
+        (Some(_), false) => { commands.entity(boss_e).remove::<Health>(); }
+        _ => {}
+    }
 
     // Damage
-    for Damaged{ entity: e, .. } in damage.iter() {
-        if *e == boss_e {
-            match state.step {
-                Boss3Step::Step2 | Boss3Step::AfterJump => {
-                    if state.hp > 6 {
-                        commands.entity(boss_e).insert(Hurt::new(params::ENEMY_HURT_TIME));
-                        state.hp -= 1;
-                    }
-                }
-                Boss3Step::Step3 => {
-                    commands.entity(boss_e).insert(Hurt::new(params::ENEMY_HURT_TIME));
-                    state.hp -= 1;
-                }
-                _ => {}
-            }
-        }
+    for Wounded{ entity: e, damage, .. } in wounded.iter() {
+        if *e == boss_e { fight.hit(*damage); }
+    }
+
+    // The health mirrors the boss HP
+    let hp = fight.hp as usize;
+    if let Some(mut health) = health { if health.hp != hp { health.hp = hp; } }
+
+    for BossPhaseEvent { boss, phase } in phases.iter() {
+        if *boss != boss_e { continue; }
+        match phase {
+            0 => step.set_if_neq(AnimStep::Walk),
+            1 => step.set_if_neq(AnimStep::Prejump),
+            2 => step.set_if_neq(AnimStep::Jump),
+            _ => step.set_if_neq(AnimStep::Dash),
+        };
+        state.jump = false;
     }
 
     // Movement
-    match state.step {
-        Boss3Step::Sleep => {
-            if data.has_flag(Flags::Boss3Start) {
-                state.step = Boss3Step::Transform;
-                step.set_if_neq(AnimStep::Walk);
-            }
-        }
-        Boss3Step::Transform => {
-            state.timer += time.delta_seconds();
-            if state.timer >= 4.0 {
-                state.timer = 0.0;
-                state.step = Boss3Step::Step1;
-            }
-        }
-        Boss3Step::Step1 => {
+    match fight.attack() {
+        Some(BossAttack::Levitate) => {
             boss_pos.translation.y = params::BOSS3_GROUND
                 + params::BOSS3_LEVITATION_Y
                 + params::BOSS3_LEVITATION_AMPLITUDE * (time.elapsed_seconds() * params::BOSS3_LEVITATION_SPEED).cos();
 
-            if state.jump == false {
-                // Spawn 2 big enemies
-                for (dx, dy) in [(-32., 32.), (32., 32.)] {
-                    commands.
-                        spawn(SpriteSheetBundle {
-                            texture_atlas: textures.zombie_2_l.clone(),
-                            transform: Transform::from_xyz(boss_pos.translation.x + dx, boss_pos.translation.y + dy, params::z_pos::PLAYER),
-                            ..default()
-                        })
-                        .insert(Zombie::from_dir(-1.))
-                        .insert(EntityID::Zombie(2))
-                        .insert(get_enemy("ZombieL").expect("No such enemy"))
-                        .insert(Hitbox)
-                        .insert(ColliderBundle {
-                            collider: colliders::zombie(2),
-                            rigid_body: RigidBody::Dynamic,
-                            rotation_constraints: LockedAxes::ROTATION_LOCKED,
-                            ..default()
-                        })
-                        .insert(Boss3Invoc)
-                    ;
-                }
-
-                state.jump = true;
-            } else {
-                if invocations.is_empty() {
-                    state.step = Boss3Step::BeforeStep2;
-                }
+            if !minions.iter().any(|BossMinion(owner)| *owner == boss_e) {
+                fight.next_attack();
             }
-
-            step.set_if_neq(AnimStep::Prejump);
         }
-        Boss3Step::BeforeStep2 => {
+        Some(BossAttack::Land) => {
             let dy = boss_pos.translation.y - params::BOSS3_GROUND;
             boss_pos.translation.y -= dy.min(params::BOSS3_FALL_SPEED);
             if dy <= 0.0 {
-                state.step = Boss3Step::Step2;
-                step.set_if_neq(AnimStep::Jump);
-                data.set_flag(Flags::SizeS);
+                fight.next_attack();
             }
         }
-        Boss3Step::Step2 => {
+        Some(BossAttack::Jump) => {
             if !state.jump {
                 let boss_x = boss_pos.translation.x - 9.5;
                 // JUMP DISTANCE
@@ -208,74 +137,45 @@ pub fn update(
 
                 // X DESTINATION
                 if player_pos.translation.x < boss_x {
-                    state.x_target = boss_x - dx - 4.0;
-                    state.left = true;
                     state.x_speed = -dx / params::BOSS3_JUMP_DURATION;
                 }
                 else {
-                    state.x_target = boss_x + dx + 4.0;
                     state.x_speed = dx / params::BOSS3_JUMP_DURATION;
                 }
 
                 state.jump = true;
-                state.timer = 0.0;
-            } else {
-                state.timer += time.delta_seconds();
+                fight.timer = 0.0;
+            } else if fight.timer < params::BOSS3_JUMP_DURATION {
                 boss_pos.translation.x += state.x_speed * time.delta_seconds();
-                boss_pos.translation.y = params::BOSS3_GROUND + (state.timer * PI / params::BOSS3_JUMP_DURATION).sin() * params::BOSS3_JUMP_HEIGHT;
-                if state.timer >= params::BOSS3_JUMP_DURATION {
-                    state.step = Boss3Step::AfterJump;
-                    state.timer = params::BOSS3_AFTER_JUMP;
-                }
-            }
-        }
-        Boss3Step::AfterJump => {
-            state.timer -= time.delta_seconds();
-            if state.timer <= 0.0 {
-                if state.hp > 6 {
-                    state.jump = false;
-                    state.step = Boss3Step::Step2;
-                } else {
+                boss_pos.translation.y = params::BOSS3_GROUND + (fight.timer * PI / params::BOSS3_JUMP_DURATION).sin() * params::BOSS3_JUMP_HEIGHT;
+            } else {
+                // Rest after the jump
+                boss_pos.translation.y = params::BOSS3_GROUND;
+                if fight.timer >= params::BOSS3_JUMP_DURATION + params::BOSS3_AFTER_JUMP {
                     state.jump = false;
-                    state.step = Boss3Step::BeforeDash;
-                    state.timer = 0.0;
-                    step.set_if_neq(AnimStep::Dash);
+                    fight.next_attack();
                 }
             }
         }
-        Boss3Step::BeforeDash => {
+        Some(BossAttack::Center) => {
             if boss_pos.translation.x < params::BOSS3_X {
                 boss_pos.translation.x += 0.5;
             } else {
                 boss_pos.translation.x -= 0.5;
             }
+            // The boss may be hit out of a jump
+            let dy = boss_pos.translation.y - params::BOSS3_GROUND;
+            boss_pos.translation.y -= dy.min(params::BOSS3_FALL_SPEED);
 
-            if (boss_pos.translation.x - params::BOSS3_X).abs() < 1.0 {
-                state.step = Boss3Step::Step3;
-                state.timer = 0.0;
-                data.set_flag(Flags::SizeL);
+            if (boss_pos.translation.x - params::BOSS3_X).abs() < 1.0 && dy <= 0.0 {
+                state.sweep = 0.0;
+                fight.next_attack();
             }
         }
-        Boss3Step::Step3 => {
-            state.timer += time.elapsed_seconds();
-            boss_pos.translation.x = params::BOSS3_X + (state.timer / PI / 128.0 / 3.0).cos() * 128.0;
+        Some(BossAttack::Sweep) => {
+            state.sweep += time.elapsed_seconds();
+            boss_pos.translation.x = params::BOSS3_X + (state.sweep / PI / 128.0 / 3.0).cos() * 128.0;
         }
         _ => {}
     }
-
-    let dead = state.hp == 0 || data.has_flag(Flags::Boss3Defeated);
-
-    // Boss killed
-    if dead {
-        // Kill animation
-        if !data.has_flag(Flags::Boss3Defeated) {
-            data.set_flag(Flags::Boss3Defeated);
-            commands.insert_resource(BossKilled::new(3));
-            commands.insert_resource(Cutscene::from(&cutscenes::OUTRO));
-        }
-        step.set_if_neq(AnimStep::Fall);
-        // Remove colliders
-        commands.entity(boss_e).remove::<Collider>();
-    } else {
-    }
-}
\ No newline at end of file
+}
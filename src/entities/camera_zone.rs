@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::{EntityInstance, LdtkEntity};
+
+/// Area the camera view is kept in while the player is inside, framing the whole zone when it fits.
+/// Used by boss arenas.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct CameraZone;
+
+#[derive(Debug, Bundle, Default, LdtkEntity)]
+pub struct CameraZoneBundle {
+    camera_zone: CameraZone,
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+}
+
+impl CameraZone {
+    /// World rect of a zone
+    pub fn rect(transform: &GlobalTransform, instance: &EntityInstance) -> Rect {
+        Rect::from_center_size(
+            transform.translation().truncate(),
+            Vec2::new(instance.width as f32, instance.height as f32),
+        )
+    }
+}
@@ -1,7 +1,15 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::{EntityInstance, ldtk::FieldValue, LdtkEntity};
+use bevy_particle_systems::{Curve, CurvePoint, JitteredValue, ParticleSystem, ParticleSystemBundle, ParticleTexture, Playing};
+use bevy_particle_systems::ColorOverTime::Gradient;
 
-use crate::logic::LevelManager;
+use crate::graphics::ToastEvent;
+use crate::logic::{GameData, LevelManager, PlayerLife};
+use crate::music::{PlaySFXEvent, SFX};
+use crate::params;
+use crate::params::z_pos;
+use crate::screens::Textures;
+use crate::util::get_ldtk_field_bool;
 
 use super::player::Player;
 
@@ -16,6 +24,8 @@ pub struct CheckpointBundle {
 #[derive(Debug, Component, Default)]
 pub struct Checkpoint {
     spawner_iid: String,
+    /// Resting here brings back the enemies killed since the last rest
+    restore_enemies: bool,
 }
 
 impl From<&EntityInstance> for Checkpoint {
@@ -29,16 +39,73 @@ impl From<&EntityInstance> for Checkpoint {
             FieldValue::EntityRef(value) => value.clone().expect("pos_id field must not be empty"),
             _ => panic!("pos_id field must be a string"),
         };
-        Checkpoint { spawner_iid: spawner_ref.entity_iid }
+        Checkpoint {
+            spawner_iid: spawner_ref.entity_iid,
+            restore_enemies: get_ldtk_field_bool(&entity_instance.field_instances, "RestoreEnemies").unwrap_or(true),
+        }
+    }
+}
+
+/// Set while the player stands in a checkpoint, with the iid of its spawner
+#[derive(Resource)]
+pub struct AtCheckpoint(pub String);
+
+/// Flame at the bottom of a checkpoint, lit while it is the active one
+#[derive(Component)]
+pub struct CheckpointFlame;
+
+pub fn init_checkpoints(
+    mut commands: Commands,
+    textures: Option<Res<Textures>>,
+    checkpoints: Query<(Entity, &EntityInstance), Added<Checkpoint>>,
+) {
+    let Some(textures) = textures else { return };
+
+    for (e, instance) in checkpoints.iter() {
+        let ground = -(instance.height as f32) / 2.;
+        commands.entity(e).with_children(|builder| {
+            builder.spawn(SpriteBundle {
+                texture: textures.pixel.clone(),
+                sprite: Sprite {
+                    color: Color::rgb(0.3, 0.3, 0.35),
+                    custom_size: Some(Vec2::new(params::CHECKPOINT_BASE.0, params::CHECKPOINT_BASE.1)),
+                    anchor: bevy::sprite::Anchor::BottomCenter,
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, ground, z_pos::IMAGE_ENTITY),
+                ..default()
+            });
+            builder
+                .spawn(SpriteBundle {
+                    texture: textures.pixel.clone(),
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::new(params::CHECKPOINT_FLAME.0, params::CHECKPOINT_FLAME.1)),
+                        anchor: bevy::sprite::Anchor::BottomCenter,
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, ground + params::CHECKPOINT_BASE.1, z_pos::IMAGE_ENTITY),
+                    ..default()
+                })
+                .insert(CheckpointFlame)
+            ;
+        });
     }
 }
 
 pub fn check_player_in_checkpoint(
+    mut commands: Commands,
+    textures: Res<Textures>,
     player: Query<&GlobalTransform, With<Player>>,
     mut level_manager: ResMut<LevelManager>,
+    mut game_data: ResMut<GameData>,
+    mut player_life: ResMut<PlayerLife>,
     checkpoints: Query<(&GlobalTransform, &Checkpoint, &EntityInstance)>,
+    mut sfx: EventWriter<PlaySFXEvent>,
+    mut toast: EventWriter<ToastEvent>,
+    at_checkpoint: Option<Res<AtCheckpoint>>,
 ) {
-    let Ok(transform) = player.get_single() else { return }; 
+    let Ok(transform) = player.get_single() else { return };
+    let mut inside = None;
 
     for (checkpoint_transform, checkpoint, entity_instance) in checkpoints.iter() {
         let checkpoint_pos = checkpoint_transform.translation().truncate();
@@ -49,10 +116,83 @@ pub fn check_player_in_checkpoint(
             checkpoint_pos.x + entity_instance.width as f32 / 2.,
             checkpoint_pos.y + entity_instance.height as f32 / 2.,
         );
-        
-        if checkpoint_rect.contains(player_pos) && *level_manager.spawner_uuid() != checkpoint.spawner_iid {
+
+        if !checkpoint_rect.contains(player_pos) { continue }
+        inside = Some(&checkpoint.spawner_iid);
+
+        // Rest every time the player steps in, not only when the checkpoint changes
+        if at_checkpoint.as_ref().is_some_and(|at| at.0 == checkpoint.spawner_iid) { continue }
+
+        if *level_manager.spawner_uuid() != checkpoint.spawner_iid {
             info!("Set checkpoint to {}", checkpoint.spawner_iid);
             level_manager.set_spawner_iid(checkpoint.spawner_iid.clone());
         }
+
+        // Rest, the spawner is saved with the game data
+        player_life.heal();
+        if checkpoint.restore_enemies { game_data.rest(); }
+        // Unlocked as a fast travel destination
+        if !game_data.checkpoints.contains(&checkpoint.spawner_iid) {
+            game_data.checkpoints.push(checkpoint.spawner_iid.clone());
+        }
+        game_data.set_changed();
+
+        sfx.send(PlaySFXEvent(SFX::Heal));
+        toast.send(ToastEvent("Checkpoint reached".to_string()));
+        commands
+            .spawn(ParticleSystemBundle {
+                particle_system: ParticleSystem {
+                    max_particles: 64,
+                    texture: ParticleTexture::Sprite(textures.pixel.clone()),
+                    spawn_rate_per_second: 256.0.into(),
+                    initial_speed: JitteredValue::jittered(30.0, -10.0..10.0),
+                    lifetime: JitteredValue::jittered(0.5, -0.1..0.1),
+                    color: Gradient(Curve::new(vec![
+                        CurvePoint::new(Color::rgba(1.0, 0.7, 0.3, 1.0), 0.0),
+                        CurvePoint::new(Color::rgba(1.0, 0.7, 0.3, 0.0), 1.0),
+                    ])),
+                    system_duration_seconds: params::CHECKPOINT_BURST_TIME,
+                    despawn_on_finish: true,
+                    z_value_override: Some(JitteredValue::new(z_pos::PARTICLES)),
+                    ..ParticleSystem::default()
+                },
+                transform: Transform::from_xyz(
+                    checkpoint_pos.x,
+                    checkpoint_pos.y - entity_instance.height as f32 / 2. + params::CHECKPOINT_BASE.1,
+                    0.0,
+                ),
+                ..ParticleSystemBundle::default()
+            })
+            .insert(Playing)
+        ;
     }
-}
\ No newline at end of file
+
+    match (inside, at_checkpoint) {
+        (Some(iid), Some(at)) if at.0 == *iid => {}
+        (Some(iid), _) => commands.insert_resource(AtCheckpoint(iid.clone())),
+        (None, Some(_)) => commands.remove_resource::<AtCheckpoint>(),
+        (None, None) => {}
+    }
+}
+
+pub fn animate_checkpoints(
+    level_manager: Res<LevelManager>,
+    checkpoints: Query<(&Checkpoint, &Children)>,
+    mut flames: Query<(&mut Sprite, &mut Transform), With<CheckpointFlame>>,
+    time: Res<Time>,
+) {
+    for (checkpoint, children) in checkpoints.iter() {
+        let active = *level_manager.spawner_uuid() == checkpoint.spawner_iid;
+        for &child in children.iter() {
+            let Ok((mut sprite, mut pos)) = flames.get_mut(child) else { continue };
+            if active {
+                let flicker = (time.elapsed_seconds() * params::CHECKPOINT_FLICKER_SPEED).sin();
+                sprite.color = Color::rgb(1.0, 0.6 + 0.15 * flicker, 0.2);
+                pos.scale.y = 1.0 + 0.25 * flicker;
+            } else {
+                sprite.color = Color::rgb(0.4, 0.2, 0.15);
+                pos.scale.y = 0.5;
+            }
+        }
+    }
+}
@@ -1,13 +1,18 @@
+use std::str::FromStr;
+
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
 use bevy_ecs_ldtk::EntityInstance;
+use bevy_rapier2d::pipeline::QueryFilter;
+use bevy_rapier2d::plugin::RapierContext;
 
 use crate::entities::animation::{AnimStep, EntityTimer};
 use crate::entities::bird::{BirdFlag, Range};
 use crate::entities::EntityID;
+use crate::entities::pickup::DropTable;
 use crate::entities::player::{IgnoreSize, PlayerSize};
-use crate::logic::GameData;
-use crate::params;
+use crate::entities::registry::entity_def;
+use crate::logic::{Died, GameData, Respawn};
 use crate::screens::Textures;
 use crate::util::{get_ldtk_field_int, get_ldtk_field_string};
 
@@ -67,7 +72,25 @@ pub fn entity_spawned(
         }
 
         if let Some(enemy) = get_enemy(&instance.identifier) {
-            e_c.insert(enemy);
+            e_c.insert(enemy.with_fields(&instance.field_instances));
+        }
+
+        if let Some(health) = entity_def(&instance.identifier).and_then(|def| def.health) {
+            if game_data.killed.contains_key(&instance.iid) {
+                e_c.despawn_recursive();
+                continue;
+            }
+            e_c.insert(health.with_fields(&instance.field_instances));
+            if let Some(respawn) = get_ldtk_field_string(&instance.field_instances, "Respawn") {
+                match Respawn::from_str(&respawn) {
+                    Ok(respawn) => { e_c.insert(respawn); },
+                    Err(_) => error!("Unknown respawn policy: {}", respawn),
+                }
+            }
+        }
+
+        if let Some(drops) = get_ldtk_field_string(&instance.field_instances, "Drops") {
+            e_c.insert(DropTable::from(&drops));
         }
 
         // Add TextureAtlasSprite
@@ -90,24 +113,43 @@ pub fn entity_spawned(
     }
 }
 
+/// Remember the killed enemies that shouldn't come back with the level
+pub fn record_kills(
+    mut died: EventReader<Died>,
+    enemies: Query<(&EntityInstance, &Respawn)>,
+    mut game_data: ResMut<GameData>,
+) {
+    for Died { entity } in died.iter() {
+        let Ok((instance, respawn)) = enemies.get(*entity) else { continue };
+        if *respawn != Respawn::Always {
+            game_data.killed.insert(instance.iid.clone(), *respawn);
+        }
+    }
+}
+
 fn get_entity_id(instance: &EntityInstance) -> Option<EntityID> {
-    match instance.identifier.as_ref() {
-        "Player" => Some(EntityID::Player(PlayerSize::M)),
-        "Zombie" => Some(EntityID::Zombie(
-            get_ldtk_field_int(&instance.field_instances, "Size").expect("Can't find zombie size."),
-        )),
-        "Bird" => Some(EntityID::Bird(PlayerSize::S)),
-        "Boss1" => Some(EntityID::Boss1),
-        "Boss2" => Some(EntityID::Boss2),
-        "Boss3" => Some(EntityID::Boss3),
-        "OldGuy" => Some(EntityID::OldGuy),
-        "Spawner"
-        | "Checkpoint"
-        | "DamageZone"
-        | "PlayerSensor"
-        | "ImageEntity" 
-        | "Wall" => None,
-        _ => panic!("Unknown entity: {}", instance.identifier)
+    entity_def(&instance.identifier)
+        .unwrap_or_else(|| panic!("Unknown entity: {}", instance.identifier))
+        .id
+        .map(|id| id(instance))
+}
+
+/// Ray cast from an enemy eyes to the player, other colliders block the view
+pub fn line_of_sight(
+    collisions: &RapierContext,
+    enemy: Entity,
+    eye: Vec2,
+    player: Entity,
+    target: Vec2,
+    range: f32,
+) -> bool {
+    let to_player = target - eye;
+    let distance = to_player.length();
+    if distance > range || distance == 0.0 { return false; }
+    let filter = QueryFilter::new().exclude_sensors().exclude_collider(enemy);
+    match collisions.cast_ray(eye, to_player / distance, distance, true, filter) {
+        Some((e, _)) => e == player,
+        None => true,
     }
 }
 
@@ -121,30 +163,9 @@ pub fn add_initial_y(
 }
 
 pub fn sprite_atlas(id: &str, textures: &Res<Textures>) -> Option<Handle<TextureAtlas>> {
-    match id {
-        "Player" => Some(textures.hero_m.clone()),
-        "Zombie" => Some(textures.zombie_s.clone()),
-        "OldGuy" => Some(textures.old_guy.clone()),
-        "Bird" => Some(textures.bird.clone()),
-        "Boss1" => Some(textures.boss_1.clone()),
-        "Boss2" => Some(textures.boss_2.clone()),
-        "Boss3" => Some(textures.boss_3.clone()),
-        _ => None,
-    }
+    entity_def(id)?.atlas.map(|atlas| atlas(textures))
 }
 
 pub fn get_enemy(id: &str) -> Option<Enemy> {
-    match id {
-        "Zombie" | "ZombieL" | "Eye1" | "Bird" | "Boss1" | "Boss2" | "Eye2" | "Boss3" => Some(Enemy {
-            player_knockback_speed: params::ENEMIES_KNOCKBACK_SPEED,
-            player_knockback_time: params::ENEMIES_KNOCKBACK_TIME,
-            player_hurt_time: params::ENEMIES_KNOCKBACK_TIME,
-        }),
-        "DamageZone" => Some(Enemy {
-            player_knockback_speed: params::SPIKES_KNOCKBACK_SPEED,
-            player_knockback_time: params::SPIKES_KNOCKBACK_TIME,
-            player_hurt_time: params::SPIKES_KNOCKBACK_TIME,
-        }),
-        _ => None,
-    }
-}
\ No newline at end of file
+    entity_def(id)?.enemy
+}
@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::{EntityInstance, LdtkEntity};
+use rand::{Rng, thread_rng};
+
+use crate::graphics::Light;
+use crate::params;
+use crate::util::get_ldtk_field_float;
+
+#[derive(Debug, Bundle, Default, LdtkEntity)]
+pub struct LightBundle {
+    #[from_entity_instance]
+    light: Light,
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+}
+
+impl From<&EntityInstance> for Light {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        let fields = &entity_instance.field_instances;
+        Light {
+            radius: get_ldtk_field_float(fields, "Radius").unwrap_or(params::LIGHT_RADIUS),
+            flicker: get_ldtk_field_float(fields, "Flicker").unwrap_or(0.0),
+            phase: thread_rng().gen_range(0.0..std::f32::consts::TAU),
+        }
+    }
+}
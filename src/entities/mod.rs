@@ -1,37 +1,40 @@
+use std::str::FromStr;
+
 use bevy::app::App;
 use bevy::prelude::*;
-use bevy_ecs_ldtk::prelude::LdtkEntityAppExt;
+use bevy_ecs_ldtk::ldtk::FieldInstance;
+use strum_macros::EnumString;
 
 use crate::{GameState, logic};
-use crate::entities::bird::BirdBundle;
-use crate::entities::boss_1::Boss1Bundle;
-use crate::entities::boss_2::Boss2Bundle;
-use crate::entities::boss_3::Boss3Bundle;
-use crate::entities::old_guy::OldGuyBundle;
 use crate::entities::player::PlayerSize;
-use crate::entities::spawner::SpawnerBundle;
-use crate::entities::zombie::ZombieBundle;
-use crate::logic::Cutscene;
+use crate::logic::{Cutscene, WorldMap};
+use crate::params;
+use crate::util::{get_ldtk_field_float, get_ldtk_field_int, get_ldtk_field_string};
 
-use self::checkpoint::CheckpointBundle;
-use self::damage_zone::DamageZoneBundle;
 use self::player::PlayerHitEvent;
 
 pub mod player;
 pub mod zombie;
 pub mod damage_zone;
+pub mod camera_zone;
+mod light;
 pub mod bird;
 pub mod image_entity;
 pub mod wall;
 mod common;
 pub mod animation;
-mod checkpoint;
+pub mod checkpoint;
+pub mod boss;
 mod boss_1;
 pub mod player_sensor;
 pub(crate) mod spawner;
 mod boss_2;
 mod boss_3;
 mod old_guy;
+pub mod pickup;
+pub mod projectile;
+mod shooter;
+pub mod registry;
 
 pub struct EntitiesPlugin;
 
@@ -44,6 +47,25 @@ pub enum EntityID {
     Boss1,
     Boss2,
     Boss3,
+    /// Entities without instance specific data, named after their [registry::EntityDef]
+    Other(&'static str),
+}
+
+impl EntityID {
+    /// Identifier of the [registry::EntityDef] describing this entity
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            EntityID::Player(_) => "Player",
+            EntityID::Zombie(2) => "ZombieL",
+            EntityID::Zombie(_) => "Zombie",
+            EntityID::Bird(_) => "Bird",
+            EntityID::OldGuy => "OldGuy",
+            EntityID::Boss1 => "Boss1",
+            EntityID::Boss2 => "Boss2",
+            EntityID::Boss3 => "Boss3",
+            EntityID::Other(identifier) => identifier,
+        }
+    }
 }
 
 #[derive(Component, Clone, Debug, Default)]
@@ -55,33 +77,72 @@ pub struct Enemy {
     pub player_knockback_speed: f32,
     pub player_knockback_time: f32,
     pub player_hurt_time: f32,
+    /// Life points lost by the player on contact
+    pub damage: usize,
+    pub damage_type: DamageType,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, EnumString)]
+pub enum DamageType {
+    /// Touching an enemy
+    Contact,
+    /// Spikes and other level hazards
+    Hazard,
+    /// Enemy projectiles
+    Projectile,
+}
+
+impl DamageType {
+    fn resistance(&self, size: &PlayerSize) -> usize {
+        match self {
+            DamageType::Contact => params::CONTACT_RESISTANCE.get(size),
+            DamageType::Hazard => params::HAZARD_RESISTANCE.get(size),
+            DamageType::Projectile => params::PROJECTILE_RESISTANCE.get(size),
+        }
+    }
+
+    /// Damage taken by a player of the given size, resistances never cancel a hit that deals damage
+    pub fn damage_for(&self, damage: usize, size: &PlayerSize) -> usize {
+        if damage == 0 { return 0; }
+        damage.saturating_sub(self.resistance(size)).max(1)
+    }
+}
+
+impl Enemy {
+    /// Override the default stats with the LDtk fields of the instance
+    pub fn with_fields(mut self, fields: &Vec<FieldInstance>) -> Self {
+        if let Some(damage) = get_ldtk_field_int(fields, "Damage") { self.damage = damage; }
+        if let Some(damage_type) = get_ldtk_field_string(fields, "DamageType") {
+            match DamageType::from_str(&damage_type) {
+                Ok(damage_type) => self.damage_type = damage_type,
+                Err(_) => error!("Unknown damage type: {}", damage_type),
+            }
+        }
+        if let Some(speed) = get_ldtk_field_float(fields, "Knockback") { self.player_knockback_speed = speed; }
+        if let Some(time) = get_ldtk_field_float(fields, "KnockbackTime") {
+            self.player_knockback_time = time;
+            self.player_hurt_time = time;
+        }
+        self
+    }
 }
 
 impl Plugin for EntitiesPlugin {
     fn build(&self, app: &mut App) {
+        registry::register_ldtk_entities(app);
+
         app
             .add_event::<PlayerHitEvent>()
             .add_event::<animation::AnimationEvent>()
+            .add_event::<boss::BossPhaseEvent>()
             .add_event::<player_sensor::PlayerEnteredSensorEvent>()
             .add_event::<player_sensor::PlayerExitedSensorEvent>()
-            .register_ldtk_entity::<SpawnerBundle>("Spawner")
-            .register_ldtk_entity::<ZombieBundle>("Zombie")
-            .register_ldtk_entity::<OldGuyBundle>("OldGuy")
-            .register_ldtk_entity::<CheckpointBundle>("Checkpoint")
-            .register_ldtk_entity::<BirdBundle>("Bird")
-            .register_ldtk_entity::<Boss1Bundle>("Boss1")
-            .register_ldtk_entity::<Boss2Bundle>("Boss2")
-            .register_ldtk_entity::<Boss3Bundle>("Boss3")
-            .register_ldtk_entity::<DamageZoneBundle>("DamageZone")
-            .register_ldtk_entity::<player_sensor::PlayerSensorBundle>("PlayerSensor")
-            .register_ldtk_entity::<image_entity::ImageEntityBundle>("ImageEntity")
-            .register_ldtk_entity::<wall::WallBundle>("Wall")
             .add_systems(Update, (common::entity_spawned, common::add_initial_y))
             .add_systems(Update, (spawner::init_spawners).run_if(not(resource_exists::<spawner::SpawnersInit>())))
             .add_systems(Update, (spawner::spawn_player).run_if(resource_exists::<spawner::SpawnPlayer>()))
             .add_systems(Update,
                 (
-                    player::change_size.run_if(not(resource_exists::<Cutscene>())),
+                    player::change_size.run_if(not(resource_exists::<Cutscene>())).run_if(not(resource_exists::<WorldMap>())),
                     player::player_touches_enemy,
                     player::enemy_touches_player,
                     player::player_hit,
@@ -109,6 +170,26 @@ impl Plugin for EntitiesPlugin {
                     wall::update_walls,
                 )
             )
+            .add_systems(Update,
+                (
+                    pickup::collect_pickups,
+                    pickup::despawn_drops,
+                    zombie::zombie_behavior.before(zombie::patrol_zombie),
+                    shooter::shoot,
+                    shooter::shooter_hit,
+                    shooter::shooter_die,
+                    (projectile::move_projectiles, projectile::collide_projectiles).chain(),
+                    projectile::despawn_projectiles,
+                    boss::update_bosses.after(boss_1::update).after(boss_2::update).after(boss_3::update),
+                    boss_1::detach_eyes,
+                    wall::hit_breakable_walls,
+                    wall::break_walls,
+                    // Before the enemies are despawned
+                    common::record_kills.after(logic::apply_damage).before(zombie::zombie_die).before(shooter::shooter_die).before(wall::break_walls),
+                    checkpoint::init_checkpoints,
+                    checkpoint::animate_checkpoints,
+                ).run_if(in_state(GameState::Game))
+            )
             .add_systems(Update, (
                 animation::update_timers,
                 animation::reset_time,
@@ -122,4 +203,40 @@ impl Plugin for EntitiesPlugin {
             // .add_plugins()
         ;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damage_for_each_size_and_type() {
+        let cases = [
+            (DamageType::Contact, PlayerSize::S, 2),
+            (DamageType::Contact, PlayerSize::M, 2),
+            (DamageType::Contact, PlayerSize::L, 1),
+            (DamageType::Hazard, PlayerSize::S, 2),
+            (DamageType::Hazard, PlayerSize::M, 2),
+            (DamageType::Hazard, PlayerSize::L, 2),
+            (DamageType::Projectile, PlayerSize::S, 1),
+            (DamageType::Projectile, PlayerSize::M, 2),
+            (DamageType::Projectile, PlayerSize::L, 2),
+        ];
+        for (damage_type, size, expected) in cases {
+            assert_eq!(damage_type.damage_for(2, &size), expected, "{damage_type:?} on {size:?}");
+        }
+    }
+
+    #[test]
+    fn damage_for_never_cancels_a_hit() {
+        assert_eq!(DamageType::Contact.damage_for(1, &PlayerSize::L), 1);
+        assert_eq!(DamageType::Projectile.damage_for(1, &PlayerSize::S), 1);
+    }
+
+    #[test]
+    fn damage_for_keeps_harmless_hits_harmless() {
+        for size in [PlayerSize::S, PlayerSize::M, PlayerSize::L] {
+            assert_eq!(DamageType::Contact.damage_for(0, &size), 0);
+        }
+    }
+}
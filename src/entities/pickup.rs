@@ -0,0 +1,184 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy_ecs_ldtk::{LevelIid, Worldly};
+use bevy_rapier2d::geometry::{Collider, Sensor};
+use bevy_rapier2d::plugin::RapierContext;
+use rand::{Rng, thread_rng};
+use strum_macros::EnumString;
+
+use crate::entities::NamedEntity;
+use crate::entities::player::Player;
+use crate::logic::{GameData, LevelManager, LevelUnloadedEvent, PlayerLife};
+use crate::music::{PlaySFXEvent, SFX};
+use crate::params;
+use crate::screens::Textures;
+
+/// Item dropped by enemies
+#[derive(Copy, Clone, Eq, PartialEq, Debug, EnumString)]
+pub enum Pickup {
+    /// Restores half a heart
+    Heart,
+    /// Restores a full heart
+    FullHeart,
+}
+
+impl Pickup {
+    fn life(&self) -> usize {
+        match self {
+            Pickup::Heart => 1,
+            Pickup::FullHeart => 2,
+        }
+    }
+
+    fn sprite_index(&self) -> usize {
+        match self {
+            Pickup::Heart => 1,
+            Pickup::FullHeart => 0,
+        }
+    }
+}
+
+/// Possible drops of an enemy, with their probability.
+///
+/// Read from the LDtk "Drops" field, formatted as `Heart:0.3,FullHeart:0.05`.
+#[derive(Component, Clone, Debug, Default)]
+pub struct DropTable(pub Vec<(Pickup, f32)>);
+
+impl From<&String> for DropTable {
+    fn from(value: &String) -> Self {
+        let drops = value
+            .split(',')
+            .filter_map(|drop| {
+                let (name, chance) = drop.trim().split_once(':')?;
+                match (Pickup::from_str(name.trim()), chance.trim().parse::<f32>()) {
+                    (Ok(pickup), Ok(chance)) => Some((pickup, chance)),
+                    _ => {
+                        error!("Bad drop in drop table: {}", drop);
+                        None
+                    }
+                }
+            })
+            .collect();
+        DropTable(drops)
+    }
+}
+
+impl DropTable {
+    /// Rolls the table once, returns at most one item
+    pub fn roll(&self) -> Option<Pickup> {
+        let mut roll = thread_rng().gen_range(0.0..1.0);
+        for &(pickup, chance) in &self.0 {
+            if roll < chance { return Some(pickup); }
+            roll -= chance;
+        }
+        None
+    }
+}
+
+#[derive(Component)]
+pub struct PickupItem(Pickup);
+
+/// Pickup dropped by an enemy, despawned with the level it was dropped in
+#[derive(Component)]
+pub struct Dropped {
+    level: Option<LevelIid>,
+}
+
+pub fn spawn_pickup(
+    commands: &mut Commands,
+    textures: &Textures,
+    pickup: Pickup,
+    pos: Vec3,
+) -> Entity {
+    commands
+        .spawn(SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: pickup.sprite_index(),
+                anchor: Anchor::BottomCenter,
+                ..default()
+            },
+            texture_atlas: textures.heart.clone(),
+            transform: Transform::from_xyz(pos.x, pos.y, params::z_pos::PICKUP),
+            ..default()
+        })
+        .insert(PickupItem(pickup))
+        .insert(Collider::cuboid(4.5, 4.0))
+        .insert(Sensor)
+        .insert(Worldly::default())
+        .id()
+}
+
+pub fn spawn_drop(
+    commands: &mut Commands,
+    textures: &Textures,
+    level_manager: &LevelManager,
+    drops: Option<&DropTable>,
+    pos: Vec3,
+) {
+    let Some(pickup) = drops.and_then(|d| d.roll()) else { return };
+    let drop = spawn_pickup(commands, textures, pickup, pos);
+    commands.entity(drop).insert(Dropped { level: level_manager.level_at(pos.truncate()).map(|l| l.level_iid()) });
+}
+
+pub fn despawn_drops(
+    mut commands: Commands,
+    drops: Query<(Entity, &Dropped)>,
+    mut level_unloaded_events: EventReader<LevelUnloadedEvent>,
+) {
+    for LevelUnloadedEvent(iid) in level_unloaded_events.iter() {
+        for (e, drop) in drops.iter() {
+            if drop.level.as_ref() == Some(iid) {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+    }
+}
+
+pub fn collect_pickups(
+    mut commands: Commands,
+    pickups: Query<(Entity, &PickupItem, Option<&NamedEntity>)>,
+    player: Query<Entity, With<Player>>,
+    collisions: Res<RapierContext>,
+    mut life: ResMut<PlayerLife>,
+    mut data: ResMut<GameData>,
+    mut sfx: EventWriter<PlaySFXEvent>,
+) {
+    let Ok(player) = player.get_single() else { return };
+
+    for (e, PickupItem(pickup), name) in &pickups {
+        if collisions.intersection_pair(e, player).is_none() { continue }
+        for _ in 0..pickup.life() { life.gain(); }
+        sfx.send(PlaySFXEvent(SFX::Heal));
+        // Named pickups are only collected once
+        if let Some(NamedEntity(name)) = name { data.removed_named.insert(name.clone()); }
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_drops() {
+        let table = DropTable::from(&" Heart:0.3, FullHeart : 0.05 ".to_string());
+        assert_eq!(table.0, vec![(Pickup::Heart, 0.3), (Pickup::FullHeart, 0.05)]);
+    }
+
+    #[test]
+    fn skips_bad_drops() {
+        let table = DropTable::from(&"Heart:0.3,Coin:0.5,FullHeart:lots,Heart".to_string());
+        assert_eq!(table.0, vec![(Pickup::Heart, 0.3)]);
+        assert!(DropTable::from(&String::new()).0.is_empty());
+    }
+
+    #[test]
+    fn rolls_within_the_chances() {
+        assert_eq!(DropTable(vec![(Pickup::FullHeart, 1.0)]).roll(), Some(Pickup::FullHeart));
+        assert_eq!(DropTable(vec![(Pickup::Heart, 0.0), (Pickup::FullHeart, 1.0)]).roll(), Some(Pickup::FullHeart));
+        assert_eq!(DropTable(vec![(Pickup::Heart, 0.0)]).roll(), None);
+        assert_eq!(DropTable::default().roll(), None);
+    }
+}
@@ -13,7 +13,7 @@ use crate::entities::animation::{AnimStep, EntityTimer};
 use crate::entities::EntityID;
 use crate::graphics::Hurt;
 use crate::graphics::particles::{PlayerSpawner, PlayFor};
-use crate::logic::{ColliderBundle, Flags, GameData, Knockback, PlayerLife};
+use crate::logic::{ColliderBundle, Combo, Flags, GameData, Knockback, PlayerLife};
 use crate::music::{PlaySFXEvent, SFX};
 use crate::params;
 use crate::screens::Textures;
@@ -58,6 +58,7 @@ pub struct PlayerBundle {
     pub collider_bundle: ColliderBundle,
     pub spatial: SpatialBundle,
     pub dash: Dash,
+    pub combo: Combo,
 }
 
 #[derive(Component, Default)]
@@ -93,7 +94,7 @@ pub fn change_size(
     mut commands: Commands,
     input: Res<Input<KeyCode>>,
     textures: Res<Textures>,
-    mut player: Query<(Entity, &mut EntityID, &AnimStep), (With<Player>, Without<Transformed>)>,
+    mut player: Query<(Entity, &mut EntityID, &AnimStep, &mut Combo), (With<Player>, Without<Transformed>)>,
     mut player_emitter: Query<(Entity, &mut Transform), With<PlayerSpawner>>,
     m_sensor: Query<(Entity), With<PlayerSizeChangeSensorM>>,
     l_sensor: Query<(Entity), With<PlayerSizeChangeSensorL>>,
@@ -102,14 +103,15 @@ pub fn change_size(
     data: Res<GameData>,
     mut sfx: EventWriter<PlaySFXEvent>,
 ) {
-    if !input.just_pressed(KeyCode::Up) && !input.just_pressed(KeyCode::Down) { return; }
+    // The size changes when an arrow is released, so that it can aim attacks while held
+    if !input.just_released(KeyCode::Up) && !input.just_released(KeyCode::Down) { return; }
 
-    let Ok((player, mut id, state)) = player.get_single_mut() else { return };
+    let Ok((player, mut id, state, mut combo)) = player.get_single_mut() else { return };
     let EntityID::Player(ref mut size) = *id else { return };
-    if *state == AnimStep::Attack { return; }
+    if std::mem::take(&mut combo.aimed) || *state == AnimStep::Attack { return; }
 
     let new_size =
-        if input.just_pressed(KeyCode::Up) { match *size {
+        if input.just_released(KeyCode::Up) { match *size {
             PlayerSize::S => PlayerSize::M,
             PlayerSize::M => PlayerSize::L,
             PlayerSize::L => PlayerSize::L,
@@ -145,7 +147,7 @@ pub fn change_size(
 
     *size = new_size;
 
-    if input.just_pressed(KeyCode::Up) { sfx.send(PlaySFXEvent(SFX::Upsize)); }
+    if input.just_released(KeyCode::Up) { sfx.send(PlaySFXEvent(SFX::Upsize)); }
     else { sfx.send(PlaySFXEvent(SFX::Downsize)); }
 
     commands
@@ -247,7 +249,7 @@ pub fn player_hit(
         if let Ok(Some(IgnoreSize(s))) = enemy_ignores.get(enemy_entity) { if *s == *size { continue } }
 
         // Damage player
-        player_life.lose();
+        player_life.lose(enemy.damage_type.damage_for(enemy.damage, size));
         sfx.send(PlaySFXEvent(SFX::PlayerHurt));
         commands
             .entity(player_entity)
@@ -42,9 +42,15 @@ pub struct PlayerSensorBundle {
     pub entity_instance: EntityInstance,
 
     sensor: Sensor,
+    #[with(sensor_collider)]
     collider: Collider,
 }
 
+/// Covers the whole entity as drawn in LDtk
+fn sensor_collider(entity_instance: &EntityInstance) -> Collider {
+    Collider::cuboid(entity_instance.width as f32 / 2., entity_instance.height as f32 / 2.)
+}
+
 impl Default for PlayerSensorBundle {
     fn default() -> Self {
         PlayerSensorBundle {
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::{LevelIid, Worldly};
+use bevy_rapier2d::geometry::{Collider, Sensor};
+use bevy_rapier2d::pipeline::QueryFilter;
+use bevy_rapier2d::plugin::RapierContext;
+
+use crate::entities::common::get_enemy;
+use crate::entities::Enemy;
+use crate::entities::player::{Player, PlayerHitEvent};
+use crate::logic::{Damaged, Hitbox, LevelManager, LevelUnloadedEvent, Sword};
+use crate::music::{PlaySFXEvent, SFX};
+use crate::params;
+use crate::screens::Textures;
+
+#[derive(Component)]
+pub struct Projectile {
+    velocity: Vec2,
+    lifetime: f32,
+    /// Entity which fired the projectile, never hit by it
+    owner: Entity,
+    /// Level the projectile was fired in
+    level: Option<LevelIid>,
+    /// Deflected by the sword, now hurts enemies instead of the player
+    deflected: bool,
+}
+
+pub fn spawn_projectile(
+    commands: &mut Commands,
+    textures: &Textures,
+    level_manager: &LevelManager,
+    owner: Entity,
+    pos: Vec2,
+    velocity: Vec2,
+) {
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.957, 0.137, 0.208),
+                custom_size: Some(Vec2::splat(params::PROJECTILE_RADIUS * 2.0)),
+                ..default()
+            },
+            texture: textures.pixel.clone(),
+            transform: Transform::from_xyz(pos.x, pos.y, params::z_pos::PROJECTILE),
+            ..default()
+        })
+        .insert(Projectile {
+            velocity,
+            lifetime: params::PROJECTILE_LIFETIME,
+            owner,
+            level: level_manager.level_at(pos).map(|l| l.level_iid()),
+            deflected: false,
+        })
+        .insert(Worldly::default())
+    ;
+}
+
+pub fn move_projectiles(
+    mut commands: Commands,
+    mut projectiles: Query<(Entity, &mut Projectile, &mut Transform)>,
+    level_manager: Res<LevelManager>,
+    time: Res<Time>,
+) {
+    for (e, mut projectile, mut pos) in projectiles.iter_mut() {
+        pos.translation += (projectile.velocity * time.delta_seconds()).extend(0.0);
+        projectile.lifetime -= time.delta_seconds();
+        if !level_manager.is_vec_inside_any_level(pos.translation.truncate()) { projectile.lifetime = 0.0; }
+        if projectile.lifetime <= 0.0 {
+            commands.entity(e).despawn_recursive();
+        }
+    }
+}
+
+pub fn collide_projectiles(
+    mut commands: Commands,
+    mut projectiles: Query<(Entity, &mut Projectile, &Transform)>,
+    collisions: Res<RapierContext>,
+    player: Query<Entity, With<Player>>,
+    swords: Query<(), With<Sword>>,
+    sensors: Query<(), With<Sensor>>,
+    enemies: Query<(), With<Enemy>>,
+    hitboxes: Query<(), With<Hitbox>>,
+    mut player_hit: EventWriter<PlayerHitEvent>,
+    mut damaged: EventWriter<Damaged>,
+    mut sfx: EventWriter<PlaySFXEvent>,
+) {
+    let enemy = get_enemy("Projectile").expect("Couldn't find enemy");
+    let player = player.get_single().ok();
+    let shape = Collider::ball(params::PROJECTILE_RADIUS);
+
+    for (e, mut projectile, pos) in projectiles.iter_mut() {
+        // Already despawned by [move_projectiles]
+        if projectile.lifetime <= 0.0 { continue }
+
+        let filter = if projectile.deflected { QueryFilter::new() } else { QueryFilter::new().exclude_collider(projectile.owner) };
+        let mut hits = vec![];
+        collisions.intersections_with_shape(pos.translation.truncate(), 0.0, &shape, filter, |hit| {
+            hits.push(hit);
+            true
+        });
+
+        for hit in hits {
+            // Deflect
+            if swords.contains(hit) {
+                if !projectile.deflected {
+                    projectile.deflected = true;
+                    projectile.velocity = -projectile.velocity * params::PROJECTILE_DEFLECT_MUL;
+                    sfx.send(PlaySFXEvent(SFX::Hurt));
+                }
+                continue;
+            }
+            if sensors.contains(hit) { continue }
+
+            // Hit the player
+            if Some(hit) == player {
+                if projectile.deflected { continue }
+                player_hit.send(PlayerHitEvent {
+                    enemy_entity: e,
+                    enemy,
+                    normal: projectile.velocity.normalize_or_zero(),
+                });
+                commands.entity(e).despawn_recursive();
+                break;
+            }
+
+            // Deflected projectiles hurt enemies, others fly through them
+            if enemies.contains(hit) {
+                if !projectile.deflected || !hitboxes.contains(hit) { continue }
+                damaged.send(Damaged {
+                    entity: hit,
+                    right_dir: projectile.velocity.x > 0.0,
+                    damage: params::PROJECTILE_DEFLECT_DAMAGE,
+                    knockback: 1.0,
+                    heavy: false,
+                });
+                commands.entity(e).despawn_recursive();
+                break;
+            }
+
+            // Level hulls, walls
+            commands.entity(e).despawn_recursive();
+            break;
+        }
+    }
+}
+
+pub fn despawn_projectiles(
+    mut commands: Commands,
+    projectiles: Query<(Entity, &Projectile)>,
+    mut level_unloaded_events: EventReader<LevelUnloadedEvent>,
+) {
+    for LevelUnloadedEvent(iid) in level_unloaded_events.iter() {
+        for (e, projectile) in projectiles.iter() {
+            if projectile.level.as_ref() == Some(iid) {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+    }
+}
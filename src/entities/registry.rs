@@ -0,0 +1,293 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_ecs_ldtk::EntityInstance;
+use bevy_ecs_ldtk::prelude::{LdtkEntity, LdtkEntityAppExt};
+use bevy_rapier2d::prelude::*;
+use lazy_static::lazy_static;
+
+use crate::definitions::colliders;
+use crate::entities::{DamageType, Enemy, EntityID};
+use crate::entities::animation::{AnimationRule, AnimStep, get_bird_rule, get_boss_1_rule, get_boss_2_rule, get_boss_3_rule, get_player_rule, get_shooter_rule, get_zombie_l_rule, get_zombie_rule};
+use crate::entities::bird::BirdBundle;
+use crate::entities::boss_1::Boss1Bundle;
+use crate::entities::boss_2::Boss2Bundle;
+use crate::entities::boss_3::Boss3Bundle;
+use crate::entities::camera_zone::CameraZoneBundle;
+use crate::entities::checkpoint::CheckpointBundle;
+use crate::entities::damage_zone::DamageZoneBundle;
+use crate::entities::image_entity::ImageEntityBundle;
+use crate::entities::light::LightBundle;
+use crate::entities::old_guy::OldGuyBundle;
+use crate::entities::player::PlayerSize;
+use crate::entities::player_sensor::PlayerSensorBundle;
+use crate::entities::shooter::ShooterBundle;
+use crate::entities::spawner::SpawnerBundle;
+use crate::entities::wall::{BreakableWallBundle, WallBundle};
+use crate::entities::zombie::{Zombie, ZombieBundle};
+use crate::logic::{ColliderBundle, Health};
+use crate::params;
+use crate::screens::Textures;
+use crate::util::get_ldtk_field_int;
+
+/// Everything the game needs to know about an entity type.
+///
+/// There is one entry per LDtk identifier, plus a few entries for entities spawned
+/// by code (boss parts, invocations). Adding a new entity type only requires a new entry
+/// in [ENTITY_DEFS].
+#[derive(Default)]
+pub struct EntityDef {
+    pub identifier: &'static str,
+    /// Registers the LDtk bundle, [None] for entities spawned by code
+    pub register: Option<fn(&mut App, &'static str)>,
+    /// [EntityID] given to the spawned entity, [None] for entities without sprite
+    pub id: Option<fn(&EntityInstance) -> EntityID>,
+    pub atlas: Option<fn(&Textures) -> Handle<TextureAtlas>>,
+    pub collider: Option<fn() -> ColliderBundle>,
+    /// Entities with enemy stats hurt the player on contact
+    pub enemy: Option<Enemy>,
+    pub animation: Option<fn(&EntityID, &AnimStep) -> AnimationRule>,
+    /// Adds the behavior components to entities spawned by code (boss minions)
+    pub spawn: Option<fn(&mut EntityCommands)>,
+    /// Entities with health can be killed by the player
+    pub health: Option<Health>,
+}
+
+fn ldtk<B: LdtkEntity + Bundle>(app: &mut App, identifier: &'static str) {
+    app.register_ldtk_entity::<B>(identifier);
+}
+
+const ENEMY: Enemy = Enemy {
+    player_knockback_speed: params::ENEMIES_KNOCKBACK_SPEED,
+    player_knockback_time: params::ENEMIES_KNOCKBACK_TIME,
+    player_hurt_time: params::ENEMIES_KNOCKBACK_TIME,
+    damage: params::DEFAULT_CONTACT_DAMAGE,
+    damage_type: DamageType::Contact,
+};
+
+const PROJECTILE: Enemy = Enemy {
+    player_knockback_speed: params::ENEMIES_KNOCKBACK_SPEED,
+    player_knockback_time: params::ENEMIES_KNOCKBACK_TIME,
+    player_hurt_time: params::ENEMIES_KNOCKBACK_TIME,
+    damage: params::PROJECTILE_DAMAGE,
+    damage_type: DamageType::Projectile,
+};
+
+const SPIKES: Enemy = Enemy {
+    player_knockback_speed: params::SPIKES_KNOCKBACK_SPEED,
+    player_knockback_time: params::SPIKES_KNOCKBACK_TIME,
+    player_hurt_time: params::SPIKES_KNOCKBACK_TIME,
+    damage: params::HAZARD_DAMAGE,
+    damage_type: DamageType::Hazard,
+};
+
+fn player_animation(id: &EntityID, step: &AnimStep) -> AnimationRule {
+    match id {
+        EntityID::Player(size) => get_player_rule(step, size),
+        _ => AnimationRule::Missing,
+    }
+}
+
+fn zombie_animation(_: &EntityID, step: &AnimStep) -> AnimationRule { get_zombie_rule(step) }
+
+fn zombie_l_animation(_: &EntityID, step: &AnimStep) -> AnimationRule { get_zombie_l_rule(step) }
+
+fn shooter_animation(_: &EntityID, step: &AnimStep) -> AnimationRule { get_shooter_rule(step) }
+
+fn bird_animation(_: &EntityID, step: &AnimStep) -> AnimationRule { get_bird_rule(step) }
+
+fn boss_1_animation(_: &EntityID, step: &AnimStep) -> AnimationRule { get_boss_1_rule(step) }
+
+fn boss_2_animation(_: &EntityID, step: &AnimStep) -> AnimationRule { get_boss_2_rule(step) }
+
+fn boss_3_animation(_: &EntityID, step: &AnimStep) -> AnimationRule { get_boss_3_rule(step) }
+
+lazy_static! {
+    static ref ENTITY_DEFS: HashMap<&'static str, EntityDef> = [
+        EntityDef {
+            identifier: "Player",
+            id: Some(|_| EntityID::Player(PlayerSize::M)),
+            atlas: Some(|t| t.hero_m.clone()),
+            collider: Some(|| ColliderBundle {
+                collider: Collider::from(PlayerSize::M),
+                rigid_body: RigidBody::KinematicPositionBased,
+                rotation_constraints: LockedAxes::ROTATION_LOCKED,
+                controller: KinematicCharacterController {
+                    autostep: Some(CharacterAutostep::default()),
+                    filter_flags: QueryFilterFlags::EXCLUDE_SENSORS,
+                    ..default()
+                },
+                ..default()
+            }),
+            animation: Some(player_animation),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Zombie",
+            register: Some(ldtk::<ZombieBundle>),
+            id: Some(|instance| EntityID::Zombie(
+                get_ldtk_field_int(&instance.field_instances, "Size").expect("Can't find zombie size."),
+            )),
+            atlas: Some(|t| t.zombie_s.clone()),
+            collider: Some(|| ColliderBundle {
+                collider: colliders::zombie(1),
+                rigid_body: RigidBody::Dynamic,
+                rotation_constraints: LockedAxes::ROTATION_LOCKED,
+                ..default()
+            }),
+            enemy: Some(ENEMY),
+            animation: Some(zombie_animation),
+            health: Some(Health::new(params::DEFAULT_ZOMBIE_LIVES)),
+            ..default()
+        },
+        EntityDef {
+            identifier: "ZombieL",
+            id: Some(|_| EntityID::Zombie(2)),
+            atlas: Some(|t| t.zombie_2_l.clone()),
+            collider: Some(|| ColliderBundle {
+                collider: colliders::zombie(2),
+                rigid_body: RigidBody::Dynamic,
+                rotation_constraints: LockedAxes::ROTATION_LOCKED,
+                ..default()
+            }),
+            enemy: Some(ENEMY),
+            animation: Some(zombie_l_animation),
+            spawn: Some(|e| { e.insert(Zombie::from_dir(-1.)); }),
+            health: Some(Health::new(params::DEFAULT_ZOMBIE_LIVES).with_knockback_resistance(params::ZOMBIE_L_KNOCKBACK_RESISTANCE)),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Shooter",
+            register: Some(ldtk::<ShooterBundle>),
+            id: Some(|_| EntityID::Other("Shooter")),
+            atlas: Some(|t| t.shooter.clone()),
+            collider: Some(|| ColliderBundle {
+                collider: colliders::zombie(1),
+                rigid_body: RigidBody::Fixed,
+                ..default()
+            }),
+            enemy: Some(ENEMY),
+            animation: Some(shooter_animation),
+            health: Some(Health::new(params::DEFAULT_SHOOTER_LIVES)),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Projectile",
+            enemy: Some(PROJECTILE),
+            ..default()
+        },
+        EntityDef {
+            identifier: "OldGuy",
+            register: Some(ldtk::<OldGuyBundle>),
+            id: Some(|_| EntityID::OldGuy),
+            atlas: Some(|t| t.old_guy.clone()),
+            animation: Some(zombie_animation),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Bird",
+            register: Some(ldtk::<BirdBundle>),
+            id: Some(|_| EntityID::Bird(PlayerSize::S)),
+            atlas: Some(|t| t.bird.clone()),
+            collider: Some(|| ColliderBundle {
+                collider: colliders::bird(),
+                rigid_body: RigidBody::KinematicVelocityBased,
+                ..default()
+            }),
+            enemy: Some(ENEMY),
+            animation: Some(bird_animation),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Boss1",
+            register: Some(ldtk::<Boss1Bundle>),
+            id: Some(|_| EntityID::Boss1),
+            atlas: Some(|t| t.boss_1.clone()),
+            collider: Some(|| ColliderBundle {
+                collider: colliders::boss1(3),
+                rigid_body: RigidBody::Fixed,
+                ..default()
+            }),
+            enemy: Some(ENEMY),
+            animation: Some(boss_1_animation),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Eye1",
+            enemy: Some(ENEMY),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Boss2",
+            register: Some(ldtk::<Boss2Bundle>),
+            id: Some(|_| EntityID::Boss2),
+            atlas: Some(|t| t.boss_2.clone()),
+            collider: Some(|| ColliderBundle {
+                collider: colliders::boss2(8),
+                rigid_body: RigidBody::Fixed,
+                ..default()
+            }),
+            enemy: Some(ENEMY),
+            animation: Some(boss_2_animation),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Eye2",
+            enemy: Some(ENEMY),
+            ..default()
+        },
+        EntityDef {
+            identifier: "Boss3",
+            register: Some(ldtk::<Boss3Bundle>),
+            id: Some(|_| EntityID::Boss3),
+            atlas: Some(|t| t.boss_3.clone()),
+            collider: Some(|| ColliderBundle {
+                collider: colliders::boss3(),
+                rigid_body: RigidBody::KinematicPositionBased,
+                ..default()
+            }),
+            enemy: Some(ENEMY),
+            animation: Some(boss_3_animation),
+            ..default()
+        },
+        EntityDef {
+            identifier: "DamageZone",
+            register: Some(ldtk::<DamageZoneBundle>),
+            collider: Some(|| ColliderBundle {
+                collider: Collider::cuboid(5., 5.),
+                rigid_body: RigidBody::Fixed,
+                ..default()
+            }),
+            enemy: Some(SPIKES),
+            ..default()
+        },
+        EntityDef { identifier: "Spawner", register: Some(ldtk::<SpawnerBundle>), ..default() },
+        EntityDef { identifier: "Checkpoint", register: Some(ldtk::<CheckpointBundle>), ..default() },
+        EntityDef { identifier: "PlayerSensor", register: Some(ldtk::<PlayerSensorBundle>), ..default() },
+        EntityDef { identifier: "ImageEntity", register: Some(ldtk::<ImageEntityBundle>), ..default() },
+        EntityDef { identifier: "Wall", register: Some(ldtk::<WallBundle>), ..default() },
+        EntityDef { identifier: "CameraZone", register: Some(ldtk::<CameraZoneBundle>), ..default() },
+        EntityDef { identifier: "Light", register: Some(ldtk::<LightBundle>), ..default() },
+        EntityDef {
+            identifier: "BreakableWall",
+            register: Some(ldtk::<BreakableWallBundle>),
+            health: Some(Health::new(params::BREAKABLE_WALL_HP)),
+            ..default()
+        },
+    ]
+        .into_iter()
+        .map(|def| (def.identifier, def))
+        .collect();
+}
+
+pub fn entity_def(identifier: &str) -> Option<&'static EntityDef> {
+    ENTITY_DEFS.get(identifier)
+}
+
+pub fn register_ldtk_entities(app: &mut App) {
+    for def in ENTITY_DEFS.values() {
+        if let Some(register) = def.register {
+            register(app, def.identifier);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::plugin::RapierContext;
+
+use crate::entities::animation::AnimStep;
+use crate::entities::common::line_of_sight;
+use crate::entities::EntityID;
+use crate::entities::pickup::{DropTable, spawn_drop};
+use crate::entities::player::Player;
+use crate::entities::projectile::spawn_projectile;
+use crate::graphics::Hurt;
+use crate::logic::{ColliderBundle, Died, Hitbox, HitStop, LevelManager, Wounded};
+use crate::params;
+use crate::screens::Textures;
+use crate::util::get_ldtk_field_float;
+
+/// Ranged enemy, fires at the player on a cooldown
+#[derive(Clone, Default, Component)]
+pub struct Shooter {
+    range: f32,
+    cooldown: f32,
+    projectile_speed: f32,
+    timer: f32,
+}
+
+impl From<&EntityInstance> for Shooter {
+    fn from(value: &EntityInstance) -> Self {
+        let fields = &value.field_instances;
+        Shooter {
+            range: get_ldtk_field_float(fields, "Range").unwrap_or(params::SHOOTER_RANGE),
+            cooldown: get_ldtk_field_float(fields, "Cooldown").unwrap_or(params::SHOOTER_COOLDOWN),
+            projectile_speed: get_ldtk_field_float(fields, "ProjectileSpeed").unwrap_or(params::PROJECTILE_SPEED),
+            timer: 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct ShooterBundle {
+    #[from_entity_instance]
+    pub shooter: Shooter,
+    #[worldly]
+    pub worldly: Worldly,
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+    #[from_entity_instance]
+    pub collider_bundle: ColliderBundle,
+    hitbox: Hitbox,
+}
+
+pub fn shoot(
+    mut commands: Commands,
+    textures: Res<Textures>,
+    level_manager: Res<LevelManager>,
+    mut shooters: Query<(Entity, &mut Shooter, &Transform, &mut TextureAtlasSprite, &mut AnimStep), Without<Hurt>>,
+    player: Query<(Entity, &Transform, &EntityID), With<Player>>,
+    collisions: Res<RapierContext>,
+    time: Res<Time>,
+) {
+    let Ok((player_e, player_pos, EntityID::Player(size))) = player.get_single() else { return };
+    let target = player_pos.translation.truncate() + vec2(0.0, size.hitbox().y / 2.0);
+
+    for (e, mut shooter, pos, mut sprite, mut step) in shooters.iter_mut() {
+        let muzzle = pos.translation.truncate() + vec2(0.0, params::SHOOTER_MUZZLE_Y);
+        if !line_of_sight(&collisions, e, muzzle, player_e, target, shooter.range) {
+            shooter.timer = 0.0;
+            step.set_if_neq(AnimStep::Idle);
+            continue;
+        }
+
+        sprite.flip_x = target.x > muzzle.x;
+        shooter.timer += time.delta_seconds();
+
+        // Telegraph the shot
+        if shooter.timer >= shooter.cooldown - params::SHOOTER_WINDUP {
+            step.set_if_neq(AnimStep::Attack);
+        }
+
+        if shooter.timer >= shooter.cooldown {
+            shooter.timer = 0.0;
+            step.set_if_neq(AnimStep::Idle);
+            let velocity = (target - muzzle).normalize_or_zero() * shooter.projectile_speed;
+            spawn_projectile(&mut commands, &textures, &level_manager, e, muzzle, velocity);
+        }
+    }
+}
+
+pub fn shooter_hit(
+    mut shooters: Query<&mut Shooter>,
+    mut wounded: EventReader<Wounded>,
+    mut hit_stop: ResMut<HitStop>,
+) {
+    for Wounded { entity, .. } in wounded.iter() {
+        let Ok(mut shooter) = shooters.get_mut(*entity) else { continue };
+        hit_stop.time_left = params::ZOMBIE_HIT_STOP_DURATION;
+        shooter.timer = 0.0;
+    }
+}
+
+pub fn shooter_die(
+    mut commands: Commands,
+    textures: Res<Textures>,
+    mut died: EventReader<Died>,
+    level_manager: Res<LevelManager>,
+    shooters: Query<(&Transform, Option<&DropTable>), With<Shooter>>,
+) {
+    for Died { entity } in died.iter() {
+        let Ok((pos, drops)) = shooters.get(*entity) else { continue };
+        spawn_drop(&mut commands, &textures, &level_manager, drops, pos.translation);
+        commands.entity(*entity).despawn_recursive();
+    }
+}
@@ -5,7 +5,7 @@ use bevy_rapier2d::geometry::{Sensor, Collider};
 
 use crate::{params, util};
 use crate::entities::player::{Dash, Player, PlayerBundle};
-use crate::logic::{ColliderBundle, LevelManager};
+use crate::logic::{ColliderBundle, Combo, LevelManager};
 
 use super::player::{PlayerSizeChangeSensorL, PlayerSizeChangeSensorM, PlayerSize};
 
@@ -112,6 +112,7 @@ pub fn spawn_player(
         instance,
         spatial: SpatialBundle::from_transform(transform),
         dash: Dash::default(),
+        combo: Combo::default(),
     })
     .with_children(|cb| {
         cb.spawn((
@@ -1,10 +1,16 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::{LdtkEntity, EntityInstance};
+use bevy_particle_systems::{Curve, CurvePoint, JitteredValue, ParticleSystem, ParticleSystemBundle, ParticleTexture, Playing};
+use bevy_particle_systems::ColorOverTime::Gradient;
 use bevy_rapier2d::geometry::Collider;
 
-use crate::{logic::{Flags, GameData}};
+use crate::{logic::{Died, Flags, GameData, Hitbox, LevelManager, Wounded}, params};
+use crate::music::{PlaySFXEvent, SFX};
+use crate::params::z_pos;
+use crate::screens::{ScreenShake, Textures};
 
 use super::{NamedEntity, image_entity::ImageEntity};
+use super::pickup::{DropTable, spawn_drop};
 
 
 
@@ -70,3 +76,88 @@ pub fn update_walls(
     }
 }
 
+/// Wall hiding a secret, destroyed by sword hits.
+///
+/// Its [crate::logic::Health] comes from the LDtk fields (`HP`, `Armored` to only break it at L size),
+/// once destroyed its `Name` stays in [GameData::removed_named].
+#[derive(Clone, Copy, Debug, Default, Component)]
+pub struct BreakableWall;
+
+#[derive(Debug, Bundle, LdtkEntity)]
+pub struct BreakableWallBundle {
+    pub wall: BreakableWall,
+    #[from_entity_instance]
+    pub entity_instance: EntityInstance,
+    collider: Collider,
+    #[from_entity_instance]
+    pub image_entity: ImageEntity,
+    hitbox: Hitbox,
+}
+
+impl Default for BreakableWallBundle {
+    fn default() -> Self {
+        Self {
+            wall: Default::default(),
+            entity_instance: Default::default(),
+            collider: wall_collider(),
+            image_entity: Default::default(),
+            hitbox: Hitbox,
+        }
+    }
+}
+
+pub fn hit_breakable_walls(
+    mut commands: Commands,
+    mut wounded: EventReader<Wounded>,
+    walls: Query<(), With<BreakableWall>>,
+    mut sfx: EventWriter<PlaySFXEvent>,
+) {
+    for Wounded { entity, .. } in wounded.iter() {
+        if !walls.contains(*entity) { continue }
+        commands.insert_resource(ScreenShake::new(params::WALL_HIT_SHAKE));
+        sfx.send(PlaySFXEvent(SFX::Hurt));
+    }
+}
+
+pub fn break_walls(
+    mut commands: Commands,
+    textures: Res<Textures>,
+    mut died: EventReader<Died>,
+    level_manager: Res<LevelManager>,
+    walls: Query<(&Transform, Option<&NamedEntity>, Option<&DropTable>), With<BreakableWall>>,
+    mut game_data: ResMut<GameData>,
+    mut sfx: EventWriter<PlaySFXEvent>,
+) {
+    for Died { entity } in died.iter() {
+        let Ok((pos, name, drops)) = walls.get(*entity) else { continue };
+        if let Some(NamedEntity(name)) = name { game_data.removed_named.insert(name.clone()); }
+        spawn_drop(&mut commands, &textures, &level_manager, drops, pos.translation);
+
+        // Crumble
+        commands
+            .spawn(ParticleSystemBundle {
+                particle_system: ParticleSystem {
+                    max_particles: 128,
+                    texture: ParticleTexture::Sprite(textures.pixel.clone()),
+                    spawn_rate_per_second: 512.0.into(),
+                    initial_speed: JitteredValue::jittered(40.0, -20.0..20.0),
+                    lifetime: JitteredValue::jittered(0.6, -0.2..0.2),
+                    color: Gradient(Curve::new(vec![
+                        CurvePoint::new(Color::rgba(0.6, 0.55, 0.5, 1.0), 0.0),
+                        CurvePoint::new(Color::rgba(0.6, 0.55, 0.5, 0.0), 1.0),
+                    ])),
+                    system_duration_seconds: params::WALL_CRUMBLE_TIME,
+                    despawn_on_finish: true,
+                    z_value_override: Some(JitteredValue::new(z_pos::PARTICLES)),
+                    ..ParticleSystem::default()
+                },
+                transform: Transform::from_translation(pos.translation),
+                ..ParticleSystemBundle::default()
+            })
+            .insert(Playing)
+        ;
+        commands.insert_resource(ScreenShake::new(params::WALL_BREAK_SHAKE));
+        sfx.send(PlaySFXEvent(SFX::BossOut));
+        commands.entity(*entity).despawn_recursive();
+    }
+}
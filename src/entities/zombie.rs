@@ -3,32 +3,70 @@ use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::control::{KinematicCharacterController, KinematicCharacterControllerOutput};
 use bevy_rapier2d::geometry::{Sensor, TOIStatus};
+use bevy_rapier2d::pipeline::QueryFilter;
+use bevy_rapier2d::plugin::RapierContext;
 
-use crate::graphics::Hurt;
-use crate::logic::{ColliderBundle, Damaged, Hitbox, HitStop, Knockback};
-use crate::params::{DEFAULT_ZOMBIE_LIVES, DEFAULT_ZOMBIE_SPEED, ENEMY_HURT_TIME, ZOMBIE_AFRAID_SPEED_MUL, ZOMBIE_HIT_STOP_DURATION, ZOMBIE_INITIAL_KNOCKBACK_SPEED, ZOMBIE_KNOCKBACK_TIME};
+use crate::entities::animation::AnimStep;
+use crate::entities::common::line_of_sight;
+use crate::entities::EntityID;
+use crate::entities::pickup::{DropTable, spawn_drop};
+use crate::entities::player::Player;
+use crate::logic::{ColliderBundle, Died, Health, Hitbox, HitStop, Knockback, LevelManager, Wounded};
+use crate::params::{DEFAULT_ZOMBIE_SPEED, ZOMBIE_AFRAID_SPEED_MUL, ZOMBIE_HIT_STOP_DURATION, ZOMBIE_INITIAL_KNOCKBACK_SPEED, ZOMBIE_KNOCKBACK_TIME};
+use crate::params;
+use crate::screens::Textures;
 use crate::util::get_ldtk_field_float;
 
 #[derive(Clone, Default, Component)]
 pub struct Zombie {
     direction: f32,
     speed: f32,
-    lives: usize,
+    aggro_range: f32,
+    behavior: ZombieBehavior,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub enum ZombieBehavior {
+    /// Walk until a wall or a ledge
+    #[default]
+    Patrol,
+    /// Follow the player, `lost` is the time since the player was last seen
+    Chase { lost: f32 },
+    /// Telegraph, then lunge toward the player
+    Lunge { timer: f32 },
+    /// Rest after a lunge
+    Recover { timer: f32 },
 }
 
 impl Zombie {
     pub fn from_dir(direction: f32) -> Self {
+        Zombie::new(direction, DEFAULT_ZOMBIE_SPEED)
+    }
+
+    pub fn new(direction: f32, speed: f32) -> Self {
         Zombie {
-            direction: direction.signum() * DEFAULT_ZOMBIE_SPEED,
-            speed: DEFAULT_ZOMBIE_SPEED,
-            lives: DEFAULT_ZOMBIE_LIVES,
+            direction: direction.signum() * speed,
+            speed,
+            aggro_range: params::ZOMBIE_AGGRO_RANGE,
+            behavior: ZombieBehavior::Patrol,
         }
     }
+
+    fn face(&mut self, right: bool) {
+        self.direction = if right { self.speed } else { -self.speed };
+    }
 }
 
 impl From<&EntityInstance> for Zombie {
     fn from(value: &EntityInstance) -> Self {
-        Zombie::from_dir(get_ldtk_field_float(&value.field_instances, "Direction").unwrap_or(0.0))
+        let fields = &value.field_instances;
+        Zombie {
+            aggro_range: get_ldtk_field_float(fields, "AggroRange").unwrap_or(params::ZOMBIE_AGGRO_RANGE),
+            ..Zombie::new(
+                get_ldtk_field_float(fields, "Direction").unwrap_or(0.0),
+                get_ldtk_field_float(fields, "Speed").unwrap_or(DEFAULT_ZOMBIE_SPEED),
+            )
+        }
     }
 }
 
@@ -45,73 +83,142 @@ pub struct ZombieBundle {
     hitbox: Hitbox,
 }
 
-pub fn patrol_zombie(
-    mut query: Query<(&mut Zombie, &mut KinematicCharacterController, Option<&KinematicCharacterControllerOutput>, &mut TextureAtlasSprite), Without<Knockback>>,
-    is_sensor: Query<&Sensor>,
+/// True if there is no ground in front of the zombie
+fn ledge_ahead(collisions: &RapierContext, zombie: Entity, pos: Vec2, direction: f32) -> bool {
+    let origin = pos + vec2(direction.signum() * params::ZOMBIE_LEDGE_LOOKAHEAD, params::ZOMBIE_EYE_Y);
+    let filter = QueryFilter::new().exclude_sensors().exclude_collider(zombie);
+    collisions.cast_ray(origin, Vec2::NEG_Y, params::ZOMBIE_LEDGE_DEPTH, true, filter).is_none()
+}
+
+pub fn zombie_behavior(
+    mut zombies: Query<(Entity, &mut Zombie, &Transform, &mut TextureAtlasSprite, Option<&mut AnimStep>), Without<Knockback>>,
+    player: Query<(Entity, &Transform, &EntityID), With<Player>>,
+    collisions: Res<RapierContext>,
     time: Res<Time>,
 ) {
-    for (mut zombie, mut controller, output, mut sprite) in query.iter_mut() {
-        //info!("dx: {dx}");
-        if let Some(output) = output {
-            for col in output.collisions.iter() {
-                if col.toi.status != TOIStatus::Converged && col.toi.status != TOIStatus::Penetrating {
-                    continue;
-                }
-                let normal = col.toi.normal1.x;
-                // info!("normal: {normal}");
-                if normal.signum() == zombie.direction.signum() || normal.abs() < 0.5 {
-                    continue;
+    let Ok((player_e, player_pos, EntityID::Player(size))) = player.get_single() else { return };
+    let target = player_pos.translation.truncate() + vec2(0.0, size.hitbox().y / 2.0);
+
+    for (e, mut zombie, pos, mut sprite, step) in zombies.iter_mut() {
+        let eye = pos.translation.truncate() + vec2(0.0, params::ZOMBIE_EYE_Y);
+        let visible = line_of_sight(&collisions, e, eye, player_e, target, zombie.aggro_range);
+        let player_right = target.x > eye.x;
+        let delta = time.delta_seconds();
+
+        let behavior = match zombie.behavior {
+            ZombieBehavior::Patrol => {
+                if visible { ZombieBehavior::Chase { lost: 0.0 } } else { ZombieBehavior::Patrol }
+            }
+            ZombieBehavior::Chase { lost } => {
+                let lost = if visible { 0.0 } else { lost + delta };
+                if visible { zombie.face(player_right); }
+                if lost >= params::ZOMBIE_GIVE_UP_TIME {
+                    ZombieBehavior::Patrol
+                } else if visible && (target.x - eye.x).abs() <= params::ZOMBIE_LUNGE_RANGE {
+                    ZombieBehavior::Lunge { timer: 0.0 }
+                } else {
+                    ZombieBehavior::Chase { lost }
                 }
-                if is_sensor.contains(col.entity) {
-                    // println!("skip sensor");
-                    continue;
+            }
+            ZombieBehavior::Lunge { timer } => {
+                let timer = timer + delta;
+                if timer >= params::ZOMBIE_LUNGE_WINDUP + params::ZOMBIE_LUNGE_TIME {
+                    ZombieBehavior::Recover { timer: 0.0 }
+                } else {
+                    ZombieBehavior::Lunge { timer }
                 }
-                // info!("zombie turn around");
-                zombie.direction = -zombie.direction;
-                sprite.flip_x = zombie.direction > 0.;
-                break;
             }
+            ZombieBehavior::Recover { timer } => {
+                let timer = timer + delta;
+                if timer < params::ZOMBIE_LUNGE_RECOVER { ZombieBehavior::Recover { timer } }
+                else if visible { ZombieBehavior::Chase { lost: 0.0 } }
+                else { ZombieBehavior::Patrol }
+            }
+        };
+
+        zombie.behavior = behavior;
+        sprite.flip_x = zombie.direction > 0.;
+        if let Some(mut step) = step {
+            step.set_if_neq(match behavior {
+                ZombieBehavior::Patrol | ZombieBehavior::Chase { .. } => AnimStep::Walk,
+                ZombieBehavior::Lunge { .. } => AnimStep::Lunge,
+                ZombieBehavior::Recover { .. } => AnimStep::Idle,
+            });
         }
-        
-        if zombie.lives == 1 {
-            controller.translation = Some(Vec2::new(zombie.speed * zombie.direction * ZOMBIE_AFRAID_SPEED_MUL, 0.));
-        } else {
-            controller.translation = Some(Vec2::new(zombie.speed * zombie.direction, 0.));
-        }
+    }
+}
+
+pub fn patrol_zombie(
+    mut query: Query<(Entity, &mut Zombie, &Transform, &mut KinematicCharacterController, Option<&KinematicCharacterControllerOutput>, &mut TextureAtlasSprite, Option<&Health>), Without<Knockback>>,
+    is_sensor: Query<&Sensor>,
+    collisions: Res<RapierContext>,
+) {
+    for (e, mut zombie, pos, mut controller, output, mut sprite, health) in query.iter_mut() {
+        let grounded = output.map(|o| o.grounded).unwrap_or(false);
+        let ledge = grounded && ledge_ahead(&collisions, e, pos.translation.truncate(), zombie.direction);
+
+        let speed_mul = match zombie.behavior {
+            ZombieBehavior::Patrol => {
+                let mut turn = ledge;
+                if let Some(output) = output {
+                    for col in output.collisions.iter() {
+                        if col.toi.status != TOIStatus::Converged && col.toi.status != TOIStatus::Penetrating {
+                            continue;
+                        }
+                        let normal = col.toi.normal1.x;
+                        if normal.signum() == zombie.direction.signum() || normal.abs() < 0.5 {
+                            continue;
+                        }
+                        if is_sensor.contains(col.entity) {
+                            continue;
+                        }
+                        turn = true;
+                        break;
+                    }
+                }
+                if turn {
+                    zombie.direction = -zombie.direction;
+                    sprite.flip_x = zombie.direction > 0.;
+                }
+                // Flee on the last life point
+                if health.map_or(false, |h| h.hp == 1) { ZOMBIE_AFRAID_SPEED_MUL } else { 1.0 }
+            }
+            ZombieBehavior::Chase { .. } => if ledge { 0.0 } else { params::ZOMBIE_CHASE_SPEED_MUL },
+            ZombieBehavior::Lunge { timer } => {
+                if ledge || timer < params::ZOMBIE_LUNGE_WINDUP { 0.0 } else { params::ZOMBIE_LUNGE_SPEED_MUL }
+            }
+            ZombieBehavior::Recover { .. } => 0.0,
+        };
+
+        controller.translation = Some(Vec2::new(zombie.speed * zombie.direction * speed_mul, 0.));
     }
 }
 
 pub fn zombie_hit(
     mut commands: Commands,
-    mut zombies: Query<(Entity, &mut Zombie), Without<Hurt>>,
-    mut damaged: EventReader<Damaged>,
+    zombies: Query<&Health, With<Zombie>>,
+    mut wounded: EventReader<Wounded>,
     mut hit_stop: ResMut<HitStop>,
 ) {
-    for Damaged { entity, right_dir} in damaged.iter() {
-        if let Ok((_, mut zombie)) = zombies.get_mut(*entity) {
-            let knockback_dir = if *right_dir { 1. } else { -1. };
-            commands.entity(*entity)
-                .insert(Hurt::new(ENEMY_HURT_TIME))
-                .insert(Knockback::new(vec2(ZOMBIE_INITIAL_KNOCKBACK_SPEED * knockback_dir, 0.), ZOMBIE_KNOCKBACK_TIME));
-            hit_stop.time_left = ZOMBIE_HIT_STOP_DURATION;
-            if zombie.lives > 0 {
-                zombie.lives -= 1;
-            } else {
-                commands.entity(*entity).despawn_recursive();
-            }
-        }
+    for Wounded { entity, right_dir, knockback, .. } in wounded.iter() {
+        let Ok(health) = zombies.get(*entity) else { continue };
+        let knockback_dir = if *right_dir { 1. } else { -1. };
+        commands.entity(*entity)
+            .insert(Knockback::new(vec2(health.knockback(ZOMBIE_INITIAL_KNOCKBACK_SPEED * knockback) * knockback_dir, 0.), ZOMBIE_KNOCKBACK_TIME));
+        hit_stop.time_left = ZOMBIE_HIT_STOP_DURATION;
     }
 }
 
 pub fn zombie_die(
     mut commands: Commands,
-    mut after_hurt: RemovedComponents<Hurt>,
-    zombie: Query<(&Zombie)>,
+    textures: Res<Textures>,
+    mut died: EventReader<Died>,
+    level_manager: Res<LevelManager>,
+    zombies: Query<(&Transform, Option<&DropTable>), With<Zombie>>,
 ) {
-    for to_kill in after_hurt.iter() {
-        let Ok(z) = zombie.get(to_kill) else { continue };
-        if z.lives <= 0 {
-            commands.entity(to_kill).despawn_recursive();
-        }
+    for Died { entity } in died.iter() {
+        let Ok((pos, drops)) = zombies.get(*entity) else { continue };
+        spawn_drop(&mut commands, &textures, &level_manager, drops, pos.translation);
+        commands.entity(*entity).despawn_recursive();
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,158 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::LevelIid;
+use bevy_particle_systems::{CircleSegment, Curve, CurvePoint, EmitterShape, JitteredValue, Line, ParticleSystem, ParticleSystemBundle, ParticleTexture, Playing};
+use bevy_particle_systems::ColorOverTime::Gradient;
+use strum_macros::EnumString;
+
+use crate::logic::{LevelManager, LevelUnloadedEvent};
+use crate::params;
+use crate::params::z_pos;
+use crate::screens::{CameraController, Textures};
+
+/// Ambient effect of a level, listed in its `Ambient` LDtk field (ex: "Dust, Fireflies")
+#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumString)]
+pub enum Ambient {
+    /// Falling from the ceiling of the caves
+    Dust,
+    Leaves,
+    Rain,
+    Fireflies,
+}
+
+impl Ambient {
+    /// Emitters are placed at the top of the view, except for the fireflies
+    fn from_top(&self) -> bool {
+        *self != Ambient::Fireflies
+    }
+
+    fn particle_system(&self, textures: &Textures) -> ParticleSystem {
+        let fade = |r: f32, g: f32, b: f32, a: f32| Gradient(Curve::new(vec![
+            CurvePoint::new(Color::rgba(r, g, b, 0.0), 0.0),
+            CurvePoint::new(Color::rgba(r, g, b, a), 0.2),
+            CurvePoint::new(Color::rgba(r, g, b, a), 0.8),
+            CurvePoint::new(Color::rgba(r, g, b, 0.0), 1.0),
+        ]));
+        let width = params::WIDTH as f32 * 1.5;
+        let falling = |angle: f32| EmitterShape::Line(Line {
+            length: width,
+            angle: JitteredValue::jittered(angle, -0.2..0.2),
+        });
+
+        let system = ParticleSystem {
+            texture: ParticleTexture::Sprite(textures.pixel.clone()),
+            looping: true,
+            system_duration_seconds: 10.0,
+            z_value_override: Some(JitteredValue::new(z_pos::PARTICLES)),
+            ..ParticleSystem::default()
+        };
+
+        match self {
+            Ambient::Dust => ParticleSystem {
+                max_particles: 256,
+                spawn_rate_per_second: 20.0.into(),
+                emitter_shape: falling(-PI / 2.),
+                initial_speed: JitteredValue::jittered(8.0, -3.0..3.0),
+                lifetime: JitteredValue::jittered(10.0, -2.0..2.0),
+                color: fade(0.6, 0.55, 0.5, 0.5),
+                ..system
+            },
+            Ambient::Leaves => ParticleSystem {
+                max_particles: 64,
+                spawn_rate_per_second: 5.0.into(),
+                emitter_shape: falling(-PI / 2. - 0.3),
+                initial_speed: JitteredValue::jittered(16.0, -4.0..4.0),
+                lifetime: JitteredValue::jittered(8.0, -2.0..2.0),
+                color: fade(0.85, 0.5, 0.2, 1.0),
+                scale: 2.0.into(),
+                ..system
+            },
+            Ambient::Rain => ParticleSystem {
+                max_particles: 512,
+                spawn_rate_per_second: 160.0.into(),
+                emitter_shape: falling(-PI / 2. - 0.15),
+                initial_speed: JitteredValue::jittered(220.0, -20.0..20.0),
+                lifetime: JitteredValue::jittered(1.2, -0.2..0.2),
+                color: fade(0.6, 0.7, 0.9, 0.6),
+                ..system
+            },
+            Ambient::Fireflies => ParticleSystem {
+                max_particles: 32,
+                spawn_rate_per_second: 4.0.into(),
+                emitter_shape: EmitterShape::CircleSegment(CircleSegment {
+                    opening_angle: 2. * PI,
+                    direction_angle: 0.0,
+                    radius: JitteredValue::jittered(width / 4., -width / 4.0..width / 4.0),
+                }),
+                initial_speed: JitteredValue::jittered(4.0, -2.0..2.0),
+                lifetime: JitteredValue::jittered(4.0, -1.0..1.0),
+                color: fade(1.0, 0.9, 0.4, 1.0),
+                ..system
+            },
+        }
+    }
+}
+
+/// Emitter of an [Ambient] effect, following the camera while its level is under it
+#[derive(Component)]
+pub struct AmbientEmitter {
+    level: LevelIid,
+    ambient: Ambient,
+}
+
+pub fn update_ambient(
+    mut commands: Commands,
+    textures: Option<Res<Textures>>,
+    level_manager: Res<LevelManager>,
+    camera: Query<&Transform, (With<CameraController>, Without<AmbientEmitter>)>,
+    mut emitters: Query<(Entity, &AmbientEmitter, &mut Transform, Option<&Playing>)>,
+) {
+    let Some(textures) = textures else { return };
+    let Ok(camera) = camera.get_single() else { return };
+    let cam = camera.translation.truncate();
+    let current = level_manager.level_at(cam);
+
+    // Only the effects of the level under the camera are playing
+    for (e, emitter, mut pos, playing) in emitters.iter_mut() {
+        let active = current.map_or(false, |level| level.level_iid() == emitter.level);
+        match (active, playing) {
+            (true, None) => { commands.entity(e).insert(Playing); }
+            (false, Some(_)) => { commands.entity(e).remove::<Playing>(); }
+            _ => {}
+        }
+        if !active { continue }
+
+        let top = params::HALF_HEIGHT * camera.scale.y * params::SCALE + params::AMBIENT_MARGIN;
+        let y = if emitter.ambient.from_top() { top } else { 0.0 };
+        pos.translation = Vec3::new(cam.x, cam.y + y, 0.0);
+    }
+
+    let Some(level) = current else { return };
+    if emitters.iter().any(|(_, emitter, _, _)| emitter.level == level.level_iid()) { return; }
+
+    for &ambient in level.ambient() {
+        commands
+            .spawn(ParticleSystemBundle {
+                particle_system: ambient.particle_system(&textures),
+                transform: Transform::from_xyz(cam.x, cam.y, 0.0),
+                ..ParticleSystemBundle::default()
+            })
+            .insert(AmbientEmitter { level: level.level_iid(), ambient })
+        ;
+    }
+}
+
+pub fn pause_unloaded_ambient(
+    mut commands: Commands,
+    emitters: Query<(Entity, &AmbientEmitter), With<Playing>>,
+    mut level_unloaded_events: EventReader<LevelUnloadedEvent>,
+) {
+    for LevelUnloadedEvent(iid) in level_unloaded_events.iter() {
+        for (e, emitter) in emitters.iter() {
+            if emitter.level == *iid {
+                commands.entity(e).remove::<Playing>();
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
 use bevy_particle_systems::{
     Curve, CurvePoint, JitteredValue, ParticleSystem, ParticleSystemBundle, ParticleTexture,
 };
@@ -8,12 +9,15 @@ use crate::{logic::Hitbox, params, screens::Textures};
 use crate::params::z_pos;
 use crate::screens::ScreenShake;
 
+use super::SpritePalette;
+use super::palette::{SpritePaletteMaterial, SpritePalettes};
 use super::particles::PlayFor;
 
 #[derive(Debug, Clone, Component)]
 pub struct Hurt {
     pub time_left: f32,
-    pub hurt_color: Color,
+    /// Colors of the sprite when it flashes
+    pub palette: SpritePalette,
     override_shake: Option<f32>,
 }
 
@@ -27,7 +31,7 @@ impl Hurt {
     pub fn new(time_left: f32) -> Self {
         Self {
             time_left,
-            hurt_color: Color::RED,
+            palette: SpritePalette::Flash,
             override_shake: None,
         }
     }
@@ -35,36 +39,88 @@ impl Hurt {
     pub fn new_with_shake(time_left: f32, shake: f32) -> Self {
         Self {
             time_left,
-            hurt_color: Color::RED,
+            palette: SpritePalette::Flash,
             override_shake: Some(shake),
         }
     }
+
+    fn flashing(&self) -> bool {
+        self.time_left > 0.0 && self.time_left % 0.1 < 0.05
+    }
 }
 
+/// Copy of a hurt sprite drawn over it with the [Hurt] palette
+#[derive(Component)]
+pub struct HurtFlash;
+
 pub fn process_hurt(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Hurt, &mut TextureAtlasSprite)>,
+    mut query: Query<(Entity, &mut Hurt), With<TextureAtlasSprite>>,
+    mut materials: ResMut<Assets<SpritePaletteMaterial>>,
+    sprite_palettes: Res<SpritePalettes>,
     time: Res<Time>
 ) {
-    for (e, mut hurt, mut sprite) in query.iter_mut() {
-        if hurt.is_added() { commands.insert_resource(ScreenShake::new(
-            match hurt.override_shake {
-                None => params::SHAKE_LEN_S,
-                Some(shake_time) => shake_time,
-            }
-        )) }
+    for (e, mut hurt) in query.iter_mut() {
+        if hurt.is_added() {
+            commands.insert_resource(ScreenShake::new(
+                match hurt.override_shake {
+                    None => params::SHAKE_LEN_S,
+                    Some(shake_time) => shake_time,
+                }
+            ));
+            let flash = commands
+                .spawn(MaterialMesh2dBundle {
+                    mesh: sprite_palettes.mesh.clone(),
+                    material: materials.add(sprite_palettes.material(hurt.palette)),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                })
+                .insert(HurtFlash)
+                .id();
+            commands.entity(e).add_child(flash);
+        }
         hurt.time_left -= time.delta_seconds();
         if hurt.time_left <= 0.0 {
-            sprite.color = Color::WHITE;
             commands.entity(e).remove::<Hurt>();
-        } else if hurt.time_left % 0.1 < 0.05 {
-            sprite.color = hurt.hurt_color;
-        } else {
-            sprite.color = Color::WHITE;
         }
     }
 }
 
+/// Show the flash on the frame of the hurt sprite, remove it once the hurt is over
+pub fn update_flashes(
+    mut commands: Commands,
+    hurt_sprites: Query<(&Hurt, &TextureAtlasSprite, &Handle<TextureAtlas>)>,
+    mut flashes: Query<(Entity, &Parent, &Handle<SpritePaletteMaterial>, &mut Transform, &mut Visibility), With<HurtFlash>>,
+    atlases: Res<Assets<TextureAtlas>>,
+    mut materials: ResMut<Assets<SpritePaletteMaterial>>,
+) {
+    for (e, parent, material, mut transform, mut visibility) in flashes.iter_mut() {
+        let Ok((hurt, sprite, atlas)) = hurt_sprites.get(parent.get()) else {
+            commands.entity(e).despawn_recursive();
+            continue;
+        };
+        visibility.set_if_neq(if hurt.flashing() { Visibility::Inherited } else { Visibility::Hidden });
+        let Some(atlas) = atlases.get(atlas) else { continue };
+        let Some(frame) = atlas.textures.get(sprite.index) else { continue };
+
+        let (min, max) = (frame.min / atlas.size, frame.max / atlas.size);
+        let (min_x, max_x) = if sprite.flip_x { (max.x, min.x) } else { (min.x, max.x) };
+        let (min_y, max_y) = if sprite.flip_y { (max.y, min.y) } else { (min.y, max.y) };
+        let rect = Vec4::new(min_x, min_y, max_x, max_y);
+        if materials.get(material).map_or(false, |m| m.rect != rect || m.texture != atlas.texture) {
+            if let Some(material) = materials.get_mut(material) {
+                material.rect = rect;
+                material.texture = atlas.texture.clone();
+            }
+        }
+
+        // Just in front of the sprite, with the same anchor
+        let size = sprite.custom_size.unwrap_or(frame.size());
+        transform.translation = (-sprite.anchor.as_vec() * size).extend(0.01);
+        transform.scale = size.extend(1.);
+    }
+}
+
 pub fn add_emitters(
     mut commands: Commands,
     new_hitboxes: Query<Entity, Added<Hitbox>>,
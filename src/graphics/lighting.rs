@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::entities::EntityID;
+use crate::entities::player::Player;
+use crate::logic::LevelManager;
+use crate::params;
+use crate::params::z_pos;
+use crate::screens::CameraController;
+
+/// Light source brightening the darkness of dark levels
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct Light {
+    pub radius: f32,
+    /// Part of the radius changing when the light flickers, 0 for steady lights
+    pub flicker: f32,
+    /// Offset of the flickering, so that lights don't flicker together
+    pub phase: f32,
+}
+
+/// Low resolution overlay covering the view, darker away from the lights.
+///
+/// The texture is computed on the CPU so that it works with WebGL2 without a custom render pass.
+#[derive(Component)]
+pub struct Darkness;
+
+pub fn init_darkness(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let (width, height) = params::DARKNESS_RES;
+    let image = Image::new_fill(
+        Extent3d { width: width as u32, height: height as u32, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+
+    commands
+        .spawn(SpriteBundle {
+            texture: images.add(image),
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(Darkness)
+    ;
+}
+
+pub fn player_light(
+    mut commands: Commands,
+    mut player: Query<(Entity, &EntityID, Option<&mut Light>), With<Player>>,
+) {
+    let Ok((e, id, light)) = player.get_single_mut() else { return };
+    let EntityID::Player(size) = id else { return };
+    let radius = params::PLAYER_LIGHT_RADIUS.get(size);
+
+    match light {
+        Some(mut light) => { if light.radius != radius { light.radius = radius; } }
+        None => { commands.entity(e).insert(Light { radius, ..default() }); }
+    }
+}
+
+/// Follow the camera, before the transforms are propagated
+pub fn follow_darkness(
+    camera: Query<&Transform, (With<CameraController>, Without<Darkness>)>,
+    mut overlay: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<Darkness>>,
+    level_manager: Res<LevelManager>,
+) {
+    let Ok(camera) = camera.get_single() else { return };
+    let Ok((mut pos, mut sprite, mut visibility)) = overlay.get_single_mut() else { return };
+    let cam = camera.translation.truncate();
+
+    if level_manager.level_at(cam).and_then(|level| level.darkness()).is_none() {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
+    visibility.set_if_neq(Visibility::Inherited);
+
+    pos.translation = cam.extend(z_pos::DARKNESS);
+    sprite.custom_size = Some(view_size(camera));
+}
+
+/// Redraw the darkness around the lights.
+///
+/// Lights are children of their level, so this runs once their global transforms are up to date.
+pub fn draw_darkness(
+    camera: Query<&GlobalTransform, With<CameraController>>,
+    overlay: Query<&Handle<Image>, With<Darkness>>,
+    lights: Query<(&GlobalTransform, &Light)>,
+    level_manager: Res<LevelManager>,
+    mut images: ResMut<Assets<Image>>,
+    time: Res<Time>,
+) {
+    let Ok(camera) = camera.get_single() else { return };
+    let Ok(handle) = overlay.get_single() else { return };
+    let camera = camera.compute_transform();
+    let cam = camera.translation.truncate();
+    let Some(darkness) = level_manager.level_at(cam).and_then(|level| level.darkness()) else { return };
+    let view = view_size(&camera);
+
+    let t = time.elapsed_seconds();
+    let lights = lights
+        .iter()
+        .map(|(transform, light)| {
+            let flicker = (t * params::LIGHT_FLICKER_SPEED + light.phase).sin()
+                * (t * params::LIGHT_FLICKER_SPEED * 0.37 + light.phase).cos();
+            (transform.translation().truncate(), light.radius * (1.0 + light.flicker * flicker))
+        })
+        .filter(|(light_pos, radius)| {
+            (*light_pos - cam).abs().cmplt(view / 2. + *radius).all()
+        })
+        .collect::<Vec<_>>();
+
+    let Some(image) = images.get_mut(handle) else { return };
+    let (width, height) = params::DARKNESS_RES;
+    let texel = view / Vec2::new(width as f32, height as f32);
+    let top_left = cam + Vec2::new(-view.x, view.y) / 2.;
+
+    for y in 0..height {
+        for x in 0..width {
+            let world = top_left + Vec2::new((x as f32 + 0.5) * texel.x, -(y as f32 + 0.5) * texel.y);
+            let dark = lights.iter().fold(darkness, |dark, (light_pos, radius)| {
+                let d = world.distance(*light_pos) / radius.max(1.0);
+                dark * (d * d).min(1.0)
+            });
+            let i = 4 * (y * width + x);
+            image.data[i + 3] = (dark * 255.0) as u8;
+        }
+    }
+}
+
+/// Visible area around the camera, in world pixels
+fn view_size(camera: &Transform) -> Vec2 {
+    Vec2::new(params::WIDTH as f32, params::HEIGHT as f32) * camera.scale.x * params::SCALE
+}
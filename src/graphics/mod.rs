@@ -1,17 +1,27 @@
 use bevy::app::App;
 use bevy::prelude::*;
+use bevy::sprite::Material2dPlugin;
 
+pub use ambient::{Ambient, AmbientEmitter};
 pub use hurt::Hurt;
-pub use palette::Palette;
+pub use lighting::Light;
+pub use palette::{Palette, PaletteSet, SpritePalette};
 pub use text::text;
 pub use text::TextStyles;
-pub use transition::ScreenTransition;
+pub use toast::ToastEvent;
+pub use transition::{ScreenTransition, TransitionEffect};
+
+use crate::GameState;
 
 mod palette;
 mod text;
 mod transition;
 mod hurt;
+mod toast;
+mod ambient;
 pub mod particles;
+pub mod parallax;
+pub mod lighting;
 
 pub struct GraphicsPlugin;
 
@@ -20,16 +30,30 @@ impl Plugin for GraphicsPlugin {
         app
             .insert_resource(ClearColor(Palette::Background.into()))
             .insert_resource(ScreenTransition::default())
-            .add_systems(Update, transition::update)
+            .add_event::<ToastEvent>()
+            .add_plugins(Material2dPlugin::<palette::ScreenMaterial>::default())
+            .add_plugins(Material2dPlugin::<palette::SpritePaletteMaterial>::default())
+            .add_systems(Startup, palette::init_sprite_palettes)
+            .add_systems(PostStartup, palette::init_screen)
+            .add_systems(Update, palette::apply_palette)
+            .add_systems(Startup, transition::init)
+            .add_systems(Update, (transition::update, transition::draw).chain())
             .add_systems(Update, (
                 particles::update_spawners,
                 particles::init_player_spawner,
                 particles::init_boss_spawner,
                 particles::update_boss_spawner,
-                hurt::process_hurt,
+                (hurt::process_hurt, hurt::update_flashes).chain(),
                 hurt::add_emitters,
                 hurt::on_hurt,
+                toast::show_toasts,
+                toast::fade_toasts,
+                ambient::update_ambient,
+                ambient::pause_unloaded_ambient,
             ))
+            .add_systems(Update, parallax::spawn_parallax.run_if(in_state(GameState::Game)))
+            .add_systems(Update, lighting::player_light)
+            .add_systems(OnEnter(GameState::Game), lighting::init_darkness)
         ;
     }
 }
\ No newline at end of file
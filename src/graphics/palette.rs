@@ -1,6 +1,19 @@
-use bevy::prelude::Color;
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::prelude::*;
+use bevy::reflect::{TypePath, TypeUuid};
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_resource::{AsBindGroup, Extent3d, ShaderRef, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages};
+use bevy::render::texture::ImageSampler;
+use bevy::render::view::RenderLayers;
+use bevy::sprite::{Material2d, MaterialMesh2dBundle, Mesh2dHandle};
 use bevy::utils::HashMap;
+use bevy_pkv::PkvStore;
 use lazy_static::lazy_static;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::params;
+use crate::screens::CameraController;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Palette {
@@ -17,4 +30,246 @@ lazy_static! {
     static ref COLORS: HashMap<Palette, Color> = HashMap::from([
         (Palette::Background, Color::WHITE),
     ]);
-}
\ No newline at end of file
+}
+
+/// Colors of the whole screen, selected in the menu and persisted
+#[derive(Serialize, Deserialize, Resource, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum PaletteSet {
+    #[default]
+    Default,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl PaletteSet {
+    const ALL: [PaletteSet; 4] = [PaletteSet::Default, PaletteSet::Protanopia, PaletteSet::Deuteranopia, PaletteSet::Tritanopia];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaletteSet::Default => "Default",
+            PaletteSet::Protanopia => "Protanopia",
+            PaletteSet::Deuteranopia => "Deuteranopia",
+            PaletteSet::Tritanopia => "Tritanopia",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let i = PaletteSet::ALL.iter().position(|set| set == self).unwrap_or(0);
+        PaletteSet::ALL[(i + 1) % PaletteSet::ALL.len()]
+    }
+
+    /// Simulation of the color vision deficiency, in RGB
+    fn deficiency(&self) -> Option<Mat3> {
+        match self {
+            PaletteSet::Default => None,
+            PaletteSet::Protanopia => Some(Mat3::from_cols_array(&[0.567, 0.558, 0.0, 0.433, 0.442, 0.242, 0.0, 0.0, 0.758])),
+            PaletteSet::Deuteranopia => Some(Mat3::from_cols_array(&[0.625, 0.7, 0.0, 0.375, 0.3, 0.3, 0.0, 0.0, 0.7])),
+            PaletteSet::Tritanopia => Some(Mat3::from_cols_array(&[0.95, 0.0, 0.0, 0.05, 0.433, 0.475, 0.0, 0.567, 0.525])),
+        }
+    }
+
+    /// Daltonize: the colors lost by the deficiency are moved to the visible channels
+    fn remap(&self, color: Vec3) -> Vec3 {
+        let Some(deficiency) = self.deficiency() else { return color };
+        let error = color - deficiency * color;
+        let shift = Vec3::new(0.0, 0.7 * error.x + error.y, 0.7 * error.x + error.z);
+        (color + shift).clamp(Vec3::ZERO, Vec3::ONE)
+    }
+
+    /// Lookup texture of [ScreenMaterial]
+    fn lut(&self) -> Image {
+        lut(|color| self.remap(color))
+    }
+}
+
+/// Colors of a single sprite, drawn over it with a [SpritePaletteMaterial]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SpritePalette {
+    /// Hurt flash, close to white with a hint of the shading so the sprite stays readable
+    Flash,
+}
+
+impl SpritePalette {
+    const ALL: [SpritePalette; 1] = [SpritePalette::Flash];
+
+    fn remap(&self, color: Vec3) -> Vec3 {
+        match self {
+            SpritePalette::Flash => Vec3::ONE.lerp(color, 0.2),
+        }
+    }
+}
+
+/// Color cube remapped by the function, see `palette_swap.wgsl` for the layout
+fn lut(remap: impl Fn(Vec3) -> Vec3) -> Image {
+    let size = params::PALETTE_LUT_SIZE;
+    let max = (size - 1) as f32;
+    let mut data = Vec::with_capacity(size * size * size * 4);
+    for g in 0..size {
+        for b in 0..size {
+            for r in 0..size {
+                let color = remap(Vec3::new(r as f32, g as f32, b as f32) / max);
+                data.extend([color.x, color.y, color.z].map(|c| (c * 255.0).round() as u8));
+                data.push(255);
+            }
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d { width: (size * size) as u32, height: size as u32, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+    );
+    image.sampler_descriptor = ImageSampler::linear();
+    image
+}
+
+/// Draws the frame rendered by the game camera, with its colors remapped through a lookup texture
+#[derive(AsBindGroup, TypeUuid, TypePath, Debug, Clone)]
+#[uuid = "6f3c1c2e-8d0a-4b6e-9a43-2f1d5c7b9e10"]
+pub struct ScreenMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    screen: Handle<Image>,
+    #[texture(2)]
+    #[sampler(3)]
+    lut: Handle<Image>,
+}
+
+impl Material2d for ScreenMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/palette_swap.wgsl".into()
+    }
+}
+
+/// Draws a frame of a sprite sheet with its colors remapped through a lookup texture
+#[derive(AsBindGroup, TypeUuid, TypePath, Debug, Clone)]
+#[uuid = "0b9d7e54-3c1a-4f2e-b8a6-5e4d2c1f7a93"]
+pub struct SpritePaletteMaterial {
+    /// Frame in the sheet, min and max UVs, swapped to flip it
+    #[uniform(0)]
+    pub rect: Vec4,
+    #[texture(1)]
+    #[sampler(2)]
+    pub texture: Handle<Image>,
+    #[texture(3)]
+    #[sampler(4)]
+    lut: Handle<Image>,
+}
+
+impl Material2d for SpritePaletteMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/sprite_palette.wgsl".into()
+    }
+}
+
+#[derive(Resource)]
+pub struct PaletteLuts(HashMap<PaletteSet, Handle<Image>>);
+
+/// Assets shared by the [SpritePaletteMaterial]s
+#[derive(Resource)]
+pub struct SpritePalettes {
+    /// Unit quad, scaled to the size of the sprite
+    pub mesh: Mesh2dHandle,
+    luts: HashMap<SpritePalette, Handle<Image>>,
+}
+
+impl SpritePalettes {
+    pub fn material(&self, palette: SpritePalette) -> SpritePaletteMaterial {
+        SpritePaletteMaterial { rect: Vec4::ZERO, texture: default(), lut: self.luts[&palette].clone() }
+    }
+}
+
+pub fn init_sprite_palettes(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let luts = SpritePalette::ALL
+        .iter()
+        .map(|palette| (*palette, images.add(lut(|color| palette.remap(color)))))
+        .collect();
+    commands.insert_resource(SpritePalettes {
+        mesh: meshes.add(shape::Quad::new(Vec2::ONE).into()).into(),
+        luts,
+    });
+}
+
+/// Render the game camera to a texture shown by a second camera through [ScreenMaterial]
+pub fn init_screen(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ScreenMaterial>>,
+    mut camera: Query<(Entity, &mut Camera), With<CameraController>>,
+    pkv: Res<PkvStore>,
+) {
+    let Ok((camera_e, mut camera)) = camera.get_single_mut() else { return };
+
+    let screen_size = Vec2::new(params::WIDTH as f32, params::HEIGHT as f32) * params::SCALE;
+    let size = Extent3d { width: screen_size.x as u32, height: screen_size.y as u32, depth_or_array_layers: 1 };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let screen = images.add(image);
+
+    camera.target = RenderTarget::Image(screen.clone());
+    // The UI is drawn by the screen camera, without the palette
+    commands.entity(camera_e).insert(UiCameraConfig { show_ui: false });
+
+    let luts = PaletteSet::ALL
+        .iter()
+        .map(|set| (*set, images.add(set.lut())))
+        .collect::<HashMap<_, _>>();
+    let palette = pkv.get::<PaletteSet>(params::PALETTE_KEY).unwrap_or_default();
+
+    let screen_layer = RenderLayers::layer(params::SCREEN_LAYER);
+    commands
+        .spawn(MaterialMesh2dBundle {
+            mesh: meshes.add(shape::Quad::new(screen_size).into()).into(),
+            material: materials.add(ScreenMaterial { screen, lut: luts[&palette].clone() }),
+            ..default()
+        })
+        .insert(screen_layer)
+    ;
+    commands
+        .spawn(Camera2dBundle {
+            camera: Camera { order: 1, ..default() },
+            camera_2d: Camera2d { clear_color: ClearColorConfig::Custom(Color::BLACK) },
+            ..default()
+        })
+        .insert(screen_layer)
+    ;
+
+    commands.insert_resource(palette);
+    commands.insert_resource(PaletteLuts(luts));
+}
+
+pub fn apply_palette(
+    palette: Option<Res<PaletteSet>>,
+    luts: Option<Res<PaletteLuts>>,
+    mut materials: ResMut<Assets<ScreenMaterial>>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    let (Some(palette), Some(luts)) = (palette, luts) else { return };
+    if !palette.is_changed() { return; }
+
+    for (_, material) in materials.iter_mut() {
+        material.lut = luts.0[&*palette].clone();
+    }
+    if pkv.set(params::PALETTE_KEY, &*palette).is_err() {
+        error!("Couldn't persist the palette.");
+    }
+}
@@ -0,0 +1,153 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::logic::LevelManager;
+use crate::params::z_pos;
+use crate::screens::CameraController;
+
+/// Image drawn behind or in front of a level, scrolling at its own speed.
+///
+/// Read from the LDtk `Parallax` level field, one `image:factor:offset_y[:tiled]` entry per layer
+/// from back to front, e.g. `parallax/caves_far.png:0.3:-40:tiled`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParallaxLayer {
+    pub image: String,
+    /// 0 stays with the camera, 1 moves with the level, more than 1 for foregrounds
+    pub factor: f32,
+    /// From the bottom of the level to the bottom of the image
+    pub offset_y: f32,
+    /// Repeat the image horizontally
+    pub tiled: bool,
+}
+
+impl FromStr for ParallaxLayer {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':').map(str::trim);
+        let image = parts.next().filter(|image| !image.is_empty()).ok_or(())?.to_string();
+        let factor = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        let offset_y = parts.next().map_or(Ok(0.), str::parse).map_err(|_| ())?;
+        let tiled = match parts.next() {
+            None => false,
+            Some("tiled") => true,
+            Some(_) => return Err(()),
+        };
+        Ok(ParallaxLayer { image, factor, offset_y, tiled })
+    }
+}
+
+/// Copy of a layer of the level under the camera, tiled layers have one on each side
+#[derive(Component)]
+pub struct Parallax {
+    level: String,
+    /// Bottom of the level, where the layer is aligned with it
+    anchor: Vec2,
+    factor: f32,
+    offset_y: f32,
+    tiled: bool,
+    /// Position of the copy, in image widths
+    copy: f32,
+    z: f32,
+}
+
+/// Replace the layers when the camera enters another level
+pub fn spawn_parallax(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_manager: Res<LevelManager>,
+    camera: Query<&Transform, With<CameraController>>,
+    layers: Query<(Entity, &Parallax)>,
+) {
+    let Ok(camera) = camera.get_single() else { return };
+    let Some(level) = level_manager.level_at(camera.translation.truncate()) else { return };
+    if layers.iter().any(|(_, layer)| layer.level == *level.id()) { return; }
+
+    layers.for_each(|(e, _)| commands.entity(e).despawn_recursive());
+
+    let rect = level.rect();
+    for (i, layer) in level.parallax().iter().enumerate() {
+        let texture: Handle<Image> = asset_server.load(layer.image.as_str());
+        // Backgrounds go between the background color of the level and its image
+        let z = if layer.factor > 1. { z_pos::PARALLAX_FRONT } else { z_pos::PARALLAX_BACK } + i as f32 * 0.01;
+        let copies: &[f32] = if layer.tiled { &[-1., 0., 1.] } else { &[0.] };
+        for &copy in copies {
+            commands
+                .spawn(SpriteBundle {
+                    texture: texture.clone(),
+                    sprite: Sprite {
+                        anchor: Anchor::BottomCenter,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(Parallax {
+                    level: level.id().clone(),
+                    anchor: Vec2::new(rect.center().x, rect.min.y),
+                    factor: layer.factor,
+                    offset_y: layer.offset_y,
+                    tiled: layer.tiled,
+                    copy,
+                    z,
+                })
+            ;
+        }
+    }
+}
+
+/// Move the layers after the camera, see [crate::screens::CameraController]
+pub fn follow_camera(
+    camera: Query<&Transform, (With<CameraController>, Without<Parallax>)>,
+    mut layers: Query<(&Parallax, &Handle<Image>, &mut Transform)>,
+    images: Res<Assets<Image>>,
+) {
+    let Ok(camera) = camera.get_single() else { return };
+    let cam = camera.translation.truncate();
+
+    for (layer, texture, mut pos) in layers.iter_mut() {
+        let base = layer.anchor + Vec2::new(0., layer.offset_y);
+        let mut target = cam - (cam - base) * layer.factor;
+
+        // Keep a copy under the camera
+        if layer.tiled {
+            let Some(width) = images.get(texture).map(|image| image.size().x) else { continue };
+            target.x += (((cam.x - target.x) / width).round() + layer.copy) * width;
+        }
+
+        pos.translation = target.extend(layer.z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_full_layer() {
+        assert_eq!(
+            ParallaxLayer::from_str("parallax/caves_far.png:0.3:-40:tiled"),
+            Ok(ParallaxLayer { image: "parallax/caves_far.png".to_string(), factor: 0.3, offset_y: -40., tiled: true }),
+        );
+    }
+
+    #[test]
+    fn offset_and_tiling_are_optional() {
+        assert_eq!(
+            ParallaxLayer::from_str("bg.png:1.5"),
+            Ok(ParallaxLayer { image: "bg.png".to_string(), factor: 1.5, offset_y: 0., tiled: false }),
+        );
+        assert_eq!(
+            ParallaxLayer::from_str(" bg.png : 0.5 : 10 "),
+            Ok(ParallaxLayer { image: "bg.png".to_string(), factor: 0.5, offset_y: 10., tiled: false }),
+        );
+    }
+
+    #[test]
+    fn rejects_bad_layers() {
+        for s in ["", ":0.5", "bg.png", "bg.png:fast", "bg.png:0.5:up", "bg.png:0.5:10:repeat"] {
+            assert_eq!(ParallaxLayer::from_str(s), Err(()), "{s:?}");
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use crate::graphics::TextStyles;
+use crate::params;
+use crate::screens::Fonts;
+
+/// Short message shown at the top of the screen, then faded out
+#[derive(Event)]
+pub struct ToastEvent(pub String);
+
+#[derive(Component)]
+pub struct Toast {
+    time_left: f32,
+}
+
+pub fn show_toasts(
+    mut commands: Commands,
+    mut events: EventReader<ToastEvent>,
+    fonts: Option<Res<Fonts>>,
+    toasts: Query<Entity, With<Toast>>,
+) {
+    let Some(fonts) = fonts else { return };
+
+    for ToastEvent(message) in events.iter() {
+        // Only the last toast is shown
+        toasts.for_each(|e| commands.entity(e).despawn_recursive());
+
+        commands
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(params::TOAST_TOP),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                z_index: ZIndex::Global(params::ui_z::TOAST),
+                ..default()
+            })
+            .insert(Toast { time_left: params::TOAST_TIME })
+            .with_children(|builder| {
+                builder.spawn(TextBundle::from_section(message.clone(), TextStyles::Basic.style(&fonts)));
+            })
+        ;
+    }
+}
+
+pub fn fade_toasts(
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut Toast, &Children)>,
+    mut texts: Query<&mut Text>,
+    time: Res<Time>,
+) {
+    for (e, mut toast, children) in toasts.iter_mut() {
+        toast.time_left -= time.delta_seconds();
+        if toast.time_left <= 0.0 {
+            commands.entity(e).despawn_recursive();
+            continue;
+        }
+
+        let alpha = (toast.time_left / params::TOAST_FADE).min(1.0);
+        for &child in children.iter() {
+            let Ok(mut text) = texts.get_mut(child) else { continue };
+            text.sections.iter_mut().for_each(|section| section.style.color.set_a(alpha));
+        }
+    }
+}
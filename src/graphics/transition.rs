@@ -1,64 +1,233 @@
-use bevy::prelude::{DetectChangesMut, EventWriter, NextState, ResMut, Resource};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 
-use crate::GameState;
+use crate::{GameState, params};
+use crate::entities::player::Player;
+use crate::logic::Frame;
 use crate::music::PlayBGMEvent;
+use crate::screens::CameraController;
 
-#[derive(Eq, PartialEq)]
+/// How the screen gets covered and revealed
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum TransitionEffect {
+    #[default]
+    Fade,
+    /// Circle closing on the player
+    Iris,
+    /// Screen pixels covered in a random order
+    Dissolve,
+}
+
+impl TransitionEffect {
+    /// Opacity of a screen pixel, `cover` going from 0 (clear) to 1 (covered)
+    fn alpha(&self, x: u32, y: u32, center: Vec2, max_radius: f32, cover: f32) -> f32 {
+        match self {
+            TransitionEffect::Fade => cover,
+            TransitionEffect::Iris => {
+                let pixel = Vec2::new(x as f32, y as f32) + 0.5;
+                if pixel.distance(center) > max_radius * (1.0 - cover) { 1.0 } else { 0.0 }
+            }
+            TransitionEffect::Dissolve => if noise(x, y) < cover { 1.0 } else { 0.0 },
+        }
+    }
+}
+
+/// Stable pseudo-random value in [0, 1) for a pixel
+fn noise(x: u32, y: u32) -> f32 {
+    let mut h = x.wrapping_mul(0x9E37_79B1) ^ y.wrapping_mul(0x85EB_CA77);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2C1B_3C6D);
+    h ^= h >> 12;
+    (h & 0xFFFF) as f32 / 65536.0
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 enum Transition {
-    Out(GameState),
+    /// Covering the screen, then switching to the state if any
+    Out(Option<GameState>),
+    /// Waiting for a [ScreenTransition::reveal]
+    Covered,
     In,
     None,
 }
 
-#[derive(Resource, Eq, PartialEq)]
+/// Screen transition, it runs on the raw time so that it works while the game is paused
+#[derive(Resource, Copy, Clone, PartialEq, Debug)]
 pub struct ScreenTransition {
     transition: Transition,
-    clock: usize,
+    effect: TransitionEffect,
+    duration: f32,
+    t: f32,
 }
 
 impl Default for ScreenTransition {
     fn default() -> Self {
-        Self { transition: Transition::None, clock: 0 }
+        Self { transition: Transition::None, effect: TransitionEffect::default(), duration: params::TRANSITION_TIME, t: 0.0 }
     }
 }
 
 impl ScreenTransition {
+    /// Cover the screen, switch to the state and reveal it
     pub fn to(state: GameState) -> Self {
-        Self { transition: Transition::Out(state), clock: 0 }
+        Self { transition: Transition::Out(Some(state)), ..default() }
+    }
+
+    /// Cover the screen until the next [ScreenTransition::reveal]
+    pub fn cover() -> Self {
+        Self { transition: Transition::Out(None), ..default() }
     }
 
     pub fn reveal() -> Self {
-        Self { transition: Transition::In, clock: 0 }
+        Self { transition: Transition::In, ..default() }
+    }
+
+    pub fn with(self, effect: TransitionEffect, duration: f32) -> Self {
+        Self { effect, duration, ..self }
+    }
+
+    /// Start from a covered screen, e.g. after a [ScreenTransition::cover]
+    pub fn covered(self) -> Self {
+        Self { t: self.duration, ..self }
     }
 
     pub fn is_none(&self) -> bool { self.transition == Transition::None }
+
+    pub fn is_covered(&self) -> bool { self.transition == Transition::Covered }
+
+    /// Jump to the end of the current step
+    pub fn skip(&mut self) { self.t = self.duration; }
+
+    /// How much the screen is covered, from 0 to 1
+    fn coverage(&self) -> Option<f32> {
+        let progress = if self.duration > 0.0 { (self.t / self.duration).clamp(0.0, 1.0) } else { 1.0 };
+        let ease = progress * progress * (3.0 - 2.0 * progress);
+        match self.transition {
+            Transition::Out(_) => Some(ease),
+            Transition::Covered => Some(1.0),
+            Transition::In => Some(1.0 - ease),
+            Transition::None => None,
+        }
+    }
+}
+
+/// Image at the game resolution covering the whole screen, UI included
+#[derive(Component)]
+pub struct TransitionOverlay;
+
+pub fn init(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let image = Image::new_fill(
+        Extent3d { width: params::WIDTH as u32, height: params::HEIGHT as u32, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+
+    commands
+        .spawn(ImageBundle {
+            image: UiImage::new(images.add(image)),
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(params::ui_z::TRANSITION),
+            ..default()
+        })
+        .insert(TransitionOverlay)
+    ;
 }
 
 pub fn update(
     mut transition: ResMut<ScreenTransition>,
     mut game_state: ResMut<NextState<GameState>>,
     mut play_bgm: EventWriter<PlayBGMEvent>,
+    time: Res<Time>,
 ) {
-    transition.clock += 1;
+    if matches!(transition.transition, Transition::None | Transition::Covered) { return; }
+
+    transition.t += time.raw_delta_seconds();
+    if transition.t < transition.duration { return; }
+
     match transition.transition {
-        Transition::Out(state) => {
-            match transition.clock {
-                1 => {
-                    if let Some(bgm) = state.bgm() { play_bgm.send(PlayBGMEvent(bgm)); }
-                    game_state.set(state);
-                    transition.set_if_neq(ScreenTransition::reveal());
-                }
-                _ => {},
-            }
+        Transition::Out(Some(state)) => {
+            if let Some(bgm) = state.bgm() { play_bgm.send(PlayBGMEvent(bgm)); }
+            game_state.set(state);
+            *transition = ScreenTransition::reveal().with(transition.effect, transition.duration);
+        }
+        Transition::Out(None) => {
+            transition.transition = Transition::Covered;
         }
         Transition::In => {
-            match transition.clock {
-                1 => {
-                    transition.set_if_neq(ScreenTransition::default());
-                }
-                _ => {},
-            }
+            *transition = ScreenTransition::default();
         }
         _ => {}
     }
-}
\ No newline at end of file
+}
+
+pub fn draw(
+    transition: Res<ScreenTransition>,
+    mut overlay: Query<(&mut Visibility, &mut BackgroundColor, &UiImage), With<TransitionOverlay>>,
+    mut frame: Query<&mut BackgroundColor, (With<Frame>, Without<TransitionOverlay>)>,
+    camera: Query<&Transform, With<CameraController>>,
+    player: Query<&GlobalTransform, With<Player>>,
+    mut images: ResMut<Assets<Image>>,
+    mut drawn: Local<Option<(TransitionEffect, f32, Vec2)>>,
+) {
+    let Ok((mut visibility, mut color, image)) = overlay.get_single_mut() else { return };
+    let cover = transition.coverage();
+    let fade = cover.filter(|_| transition.effect == TransitionEffect::Fade);
+
+    // In game, fades use the cutscene frame
+    if let Ok(mut frame) = frame.get_single_mut() {
+        let alpha = fade.unwrap_or(0.0);
+        if frame.0.a() != alpha { frame.0.set_a(alpha); }
+        if fade.is_some() {
+            visibility.set_if_neq(Visibility::Hidden);
+            return;
+        }
+    }
+
+    let Some(cover) = cover else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+
+    // A fade is a single opacity, the image stays covered
+    let alpha = fade.unwrap_or(1.0);
+    if color.0.a() != alpha { color.0.set_a(alpha); }
+    let cover = if fade.is_some() { 1.0 } else { cover };
+
+    // The iris closes on the player, in screen pixels going down
+    let size = Vec2::new(params::WIDTH as f32, params::HEIGHT as f32);
+    let center = match (transition.effect, camera.get_single(), player.get_single()) {
+        (TransitionEffect::Iris, Ok(camera), Ok(player)) => {
+            let offset = (player.translation() - camera.translation).truncate() / (camera.scale.x * params::SCALE);
+            size / 2. + Vec2::new(offset.x, -offset.y)
+        }
+        _ => size / 2.,
+    };
+
+    // Only redraw when the coverage changes, the iris also follows the player
+    if *drawn == Some((transition.effect, cover, center)) { return; }
+    let Some(image) = images.get_mut(&image.texture) else { return };
+    *drawn = Some((transition.effect, cover, center));
+
+    let max_radius = [Vec2::ZERO, Vec2::new(size.x, 0.0), Vec2::new(0.0, size.y), size]
+        .iter()
+        .fold(0.0_f32, |max, corner| max.max(corner.distance(center)));
+
+    let (width, height) = (params::WIDTH as u32, params::HEIGHT as u32);
+    for y in 0..height {
+        for x in 0..width {
+            let alpha = transition.effect.alpha(x, y, center, max_radius, cover);
+            let i = 4 * (y * width + x) as usize;
+            image.data[i + 3] = (alpha * 255.0) as u8;
+        }
+    }
+}
@@ -1,29 +1,105 @@
 use bevy::prelude::*;
 use bevy_rapier2d::control::KinematicCharacterControllerOutput;
-use bevy_rapier2d::prelude::Sensor;
+use bevy_rapier2d::prelude::{RapierContext, Sensor};
 
 use crate::definitions::colliders;
-use crate::entities::animation::{AnimationEvent, AnimStep};
-use crate::entities::EntityID;
-use crate::entities::player::Player;
+use crate::entities::animation::{AnimationEvent, AnimStep, EntityTimer};
+use crate::entities::{Enemy, EntityID};
+use crate::entities::player::{Dash, Player, PlayerSize};
 use crate::music::{PlaySFXEvent, SFX};
+use crate::params;
+
+/// Direction of a sword swing
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum AttackDir {
+    #[default]
+    Side,
+    Up,
+    /// Only while airborne, bounces the player off enemies and hazards
+    Down,
+}
+
+/// Attack in progress.
+///
+/// The direction is given by the arrow held when the attack key is pressed, or pressed
+/// before the swing. Arrows used to aim don't change the size when released, see
+/// [crate::entities::player::change_size]. Side attacks on the ground chain into a combo
+/// when the attack key is pressed again during the recoil.
+#[derive(Component, Default)]
+pub struct Combo {
+    pub dir: AttackDir,
+    /// Index of the swing in [params::COMBO_DAMAGE]
+    pub hit: usize,
+    swung: bool,
+    recoil: bool,
+    buffered: bool,
+    /// An arrow was held to aim, kept between attacks until it is released
+    pub aimed: bool,
+}
+
+impl Combo {
+    fn start(&mut self, dir: AttackDir, hit: usize) {
+        *self = Combo { dir, hit, aimed: self.aimed, ..default() };
+    }
+
+    pub fn damage(&self, size: &PlayerSize) -> usize {
+        let damage = match self.dir {
+            AttackDir::Side => params::COMBO_DAMAGE[self.hit.min(params::COMBO_DAMAGE.len() - 1)],
+            AttackDir::Up | AttackDir::Down => params::DIR_ATTACK_DAMAGE,
+        };
+        damage * params::SWORD_DAMAGE.get(size)
+    }
+}
+
+/// Player bouncing off a down-slash, the jump can't be cut short
+#[derive(Component)]
+pub struct Pogo;
+
+fn held_dir(input: &Input<KeyCode>, grounded: bool) -> Option<AttackDir> {
+    if input.pressed(KeyCode::Up) { Some(AttackDir::Up) }
+    else if input.pressed(KeyCode::Down) && !grounded { Some(AttackDir::Down) }
+    else { None }
+}
 
 pub fn attack(
-    mut player: Query<(&mut AnimStep, &KinematicCharacterControllerOutput), With<Player>>,
+    mut player: Query<(&mut AnimStep, &mut Combo, &KinematicCharacterControllerOutput), With<Player>>,
     input: Res<Input<KeyCode>>,
     mut events: EventReader<AnimationEvent>,
     mut sfx: EventWriter<PlaySFXEvent>,
 ) {
-    let Ok((mut step, output)) = player.get_single_mut() else { return };
+    let Ok((mut step, mut combo, output)) = player.get_single_mut() else { return };
 
-    if input.just_pressed(KeyCode::C) && *step != AnimStep::Attack {
-        step.set_if_neq(AnimStep::Attack);
-        // sfx.send(PlaySFXEvent(SFX::Sword));
+    if input.just_pressed(KeyCode::C) {
+        if input.pressed(KeyCode::Up) || input.pressed(KeyCode::Down) { combo.aimed = true; }
+        if *step != AnimStep::Attack {
+            combo.start(held_dir(&input, output.grounded).unwrap_or_default(), 0);
+            step.set_if_neq(AnimStep::Attack);
+            // sfx.send(PlaySFXEvent(SFX::Sword));
+        } else if combo.recoil {
+            combo.buffered = true;
+        }
+    }
+
+    // The direction can still change during the windup
+    if *step == AnimStep::Attack && !combo.swung && (input.just_pressed(KeyCode::Up) || input.just_pressed(KeyCode::Down)) {
+        combo.aimed = true;
+        if let Some(dir) = held_dir(&input, output.grounded) { combo.dir = dir; }
     }
 
     for event in events.iter() {
         match event {
-            AnimationEvent::AttackOver => { step.set_if_neq(if output.grounded { AnimStep::Idle } else { AnimStep::Fall }); }
+            AnimationEvent::AttackSwing => { combo.swung = true; }
+            AnimationEvent::AttackRecoil => { combo.recoil = true; }
+            AnimationEvent::AttackOver => {
+                let next = combo.hit + 1;
+                if combo.buffered && combo.dir == AttackDir::Side && output.grounded && next < params::COMBO_DAMAGE.len() {
+                    combo.start(AttackDir::Side, next);
+                    // Restart the animation
+                    *step = AnimStep::Attack;
+                } else {
+                    step.set_if_neq(if output.grounded { AnimStep::Idle } else { AnimStep::Fall });
+                }
+            }
             _ => ()
         }
     }
@@ -33,17 +109,24 @@ pub fn attack(
 pub struct SpawnSword(bool);
 
 #[derive(Component)]
-pub struct Sword(pub Vec<Entity>);
+pub struct Sword {
+    /// Entities already hit by this swing
+    pub hit: Vec<Entity>,
+    pub damage: usize,
+    pub knockback: f32,
+    pub heavy: bool,
+    pub dir: AttackDir,
+}
 
 pub fn update_sword(
     mut commands: Commands,
     mut events: EventReader<AnimationEvent>,
     sword: Query<Entity, With<Sword>>,
-    player: Query<(&EntityID, &Transform, &TextureAtlasSprite), With<Player>>,
+    player: Query<(&EntityID, &Transform, &TextureAtlasSprite, &Combo), With<Player>>,
     mut sfx: EventWriter<PlaySFXEvent>,
 ) {
     if let Ok(e) = sword.get_single() {
-        if let Ok((_, pos, _)) = player.get_single() {
+        if let Ok((_, pos, _, _)) = player.get_single() {
             commands.entity(e).insert(Transform::from_xyz(pos.translation.x, pos.translation.y, 0.0));
         }
     }
@@ -51,13 +134,19 @@ pub fn update_sword(
     for event in events.iter() {
         match event {
             AnimationEvent::AttackSwing => {
-                let Ok((EntityID::Player(size), pos, sprite)) = player.get_single() else { continue };
+                let Ok((EntityID::Player(size), pos, sprite, combo)) = player.get_single() else { continue };
                 commands
-                    .spawn(colliders::sword_collider(size, sprite.flip_x))
+                    .spawn(colliders::sword_collider(size, sprite.flip_x, combo.dir))
                     .insert(Sensor)
                     .insert(Transform::from_xyz(pos.translation.x, pos.translation.y, 0.0))
                     .insert(GlobalTransform::default())
-                    .insert(Sword(vec![]))
+                    .insert(Sword {
+                        hit: vec![],
+                        damage: combo.damage(size),
+                        knockback: params::SWORD_KNOCKBACK.get(size),
+                        heavy: params::SWORD_HEAVY.get(size),
+                        dir: combo.dir,
+                    })
                 ;
                 sfx.send(PlaySFXEvent(SFX::Sword));
             }
@@ -67,4 +156,62 @@ pub fn update_sword(
             _ => ()
         }
     }
-}
\ No newline at end of file
+}
+
+/// Down-slashes touching an enemy or a hazard bounce the player
+pub fn pogo(
+    mut commands: Commands,
+    swords: Query<(Entity, &Sword)>,
+    enemies: Query<Entity, With<Enemy>>,
+    collisions: Res<RapierContext>,
+    mut player: Query<(Entity, &mut AnimStep, &mut EntityTimer, &mut Dash), With<Player>>,
+    time: Res<Time>,
+) {
+    let Ok((player_e, mut step, mut timer, mut dash)) = player.get_single_mut() else { return };
+
+    for (sword_e, sword) in swords.iter() {
+        if sword.dir != AttackDir::Down { continue }
+        if !enemies.iter().any(|e| collisions.intersection_pair(sword_e, e).is_some()) { continue }
+
+        // The attack is cancelled, so is its recoil
+        commands.entity(sword_e).despawn_recursive();
+        commands.entity(player_e).insert(Pogo);
+        *step = AnimStep::Jump;
+        timer.t_0 = time.elapsed_seconds();
+        timer.time = 0.0;
+        dash.can_dash = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo(dir: AttackDir, hit: usize) -> Combo {
+        Combo { dir, hit, ..default() }
+    }
+
+    #[test]
+    fn side_swings_follow_the_combo() {
+        assert_eq!(combo(AttackDir::Side, 0).damage(&PlayerSize::M), 1);
+        assert_eq!(combo(AttackDir::Side, 1).damage(&PlayerSize::M), 1);
+        assert_eq!(combo(AttackDir::Side, 2).damage(&PlayerSize::M), 2);
+        // Past the end of the combo, the finisher is repeated
+        assert_eq!(combo(AttackDir::Side, 5).damage(&PlayerSize::M), 2);
+    }
+
+    #[test]
+    fn up_and_down_ignore_the_combo() {
+        for hit in 0..3 {
+            assert_eq!(combo(AttackDir::Up, hit).damage(&PlayerSize::M), 1);
+            assert_eq!(combo(AttackDir::Down, hit).damage(&PlayerSize::M), 1);
+        }
+    }
+
+    #[test]
+    fn size_multiplies_damage() {
+        assert_eq!(combo(AttackDir::Side, 0).damage(&PlayerSize::S), 1);
+        assert_eq!(combo(AttackDir::Side, 2).damage(&PlayerSize::L), 4);
+        assert_eq!(combo(AttackDir::Down, 0).damage(&PlayerSize::L), 2);
+    }
+}
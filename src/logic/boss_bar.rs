@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+
+use crate::{GameState, params};
+use crate::entities::boss::BossFight;
+use crate::graphics::TextStyles;
+use crate::logic::GameData;
+use crate::screens::{Fonts, GameUI};
+
+pub struct BossBarPlugin;
+
+impl Plugin for BossBarPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(GameState::Game), init_boss_bar)
+            .add_systems(Update, update_boss_bar.run_if(in_state(GameState::Game)))
+        ;
+    }
+}
+
+/// Health bar of the boss being fought
+#[derive(Component)]
+struct BossBar {
+    boss: Option<Entity>,
+    hp: u8,
+    /// Ratio shown by the damage trail, shrinks to the current HP after a short delay
+    trail: f32,
+    timer: f32,
+}
+
+#[derive(Component)]
+struct BossBarName;
+
+#[derive(Component)]
+struct BossBarFrame;
+
+#[derive(Component)]
+struct BossBarFill;
+
+#[derive(Component)]
+struct BossBarTrail;
+
+#[derive(Component)]
+struct BossBarMarker;
+
+fn init_boss_bar(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+) {
+    let gauge = |color: Color| NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        background_color: BackgroundColor(color),
+        z_index: ZIndex::Global(params::ui_z::BOSS_BAR),
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(params::BOSS_BAR_BOTTOM),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(BossBar { boss: None, hp: 0, trail: 0.0, timer: 0.0 })
+        .insert(GameUI)
+        .with_children(|builder| {
+            builder
+                .spawn(TextBundle {
+                    text: Text::from_section("", TextStyles::Basic.style(&fonts)),
+                    style: Style {
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        ..default()
+                    },
+                    z_index: ZIndex::Global(params::ui_z::BOSS_BAR),
+                    ..default()
+                })
+                .insert(BossBarName)
+            ;
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(params::BOSS_BAR_WIDTH),
+                        height: Val::Px(params::BOSS_BAR_HEIGHT),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.6)),
+                    z_index: ZIndex::Global(params::ui_z::BOSS_BAR),
+                    ..default()
+                })
+                .insert(BossBarFrame)
+                .with_children(|builder| {
+                    builder.spawn(gauge(Color::WHITE)).insert(BossBarTrail);
+                    builder.spawn(gauge(Color::rgb(0.957, 0.137, 0.208))).insert(BossBarFill);
+                })
+            ;
+        })
+    ;
+}
+
+fn update_boss_bar(
+    mut commands: Commands,
+    data: Res<GameData>,
+    bosses: Query<(Entity, &BossFight)>,
+    mut bar: Query<(&mut BossBar, &mut Visibility)>,
+    mut name: Query<&mut Text, With<BossBarName>>,
+    frame: Query<Entity, With<BossBarFrame>>,
+    markers: Query<Entity, With<BossBarMarker>>,
+    mut fill: Query<&mut Style, (With<BossBarFill>, Without<BossBarTrail>)>,
+    mut trail: Query<&mut Style, (With<BossBarTrail>, Without<BossBarFill>)>,
+    time: Res<Time>,
+) {
+    let Ok((mut bar, mut visibility)) = bar.get_single_mut() else { return };
+
+    // Start flags are removed when the player dies
+    let Some((boss_e, fight)) = bosses
+        .iter()
+        .find(|(_, fight)| data.has_flag(fight.def.intro) && !fight.dead())
+    else {
+        bar.boss = None;
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+
+    let def = fight.def;
+    let ratio = fight.hp as f32 / def.hp.max(1) as f32;
+
+    // New boss
+    if bar.boss != Some(boss_e) {
+        *bar = BossBar { boss: Some(boss_e), hp: fight.hp, trail: ratio, timer: 0.0 };
+
+        if let Ok(mut text) = name.get_single_mut() {
+            text.sections[0].value = def.name.to_string();
+        }
+
+        // Phase markers
+        markers.for_each(|marker| commands.entity(marker).despawn_recursive());
+        if let Ok(frame) = frame.get_single() {
+            for hp in def.phases.iter().filter_map(|phase| phase.until_hp).filter(|hp| *hp > 0) {
+                commands
+                    .spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent(100.0 * hp as f32 / def.hp as f32),
+                            width: Val::Px(2.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(Color::BLACK),
+                        z_index: ZIndex::Global(params::ui_z::BOSS_BAR),
+                        ..default()
+                    })
+                    .insert(BossBarMarker)
+                    .set_parent(frame)
+                ;
+            }
+        }
+    }
+
+    // Damage is shown by a trail, healing is instant
+    if fight.hp < bar.hp { bar.timer = 0.0; }
+    if fight.hp > bar.hp { bar.trail = ratio; }
+    bar.hp = fight.hp;
+
+    if bar.trail > ratio {
+        bar.timer += time.delta_seconds();
+        if bar.timer >= params::BOSS_BAR_TRAIL_DELAY {
+            bar.trail = (bar.trail - params::BOSS_BAR_TRAIL_SPEED * time.delta_seconds()).max(ratio);
+        }
+    }
+
+    if let Ok(mut style) = fill.get_single_mut() {
+        style.width = Val::Percent(100.0 * ratio);
+    }
+    if let Ok(mut style) = trail.get_single_mut() {
+        style.width = Val::Percent(100.0 * bar.trail);
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+use crate::{GameState, params};
+use crate::definitions::bosses;
+use crate::graphics::ScreenTransition;
+use crate::logic::{CSEvent, Cutscene, GameData, LevelManager, PlayerLife, WorldMap};
+
+pub struct BossRushPlugin;
+
+impl Plugin for BossRushPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, init_records)
+            .add_systems(Update, (update_boss_rush, quit_boss_rush)
+                .run_if(in_state(GameState::Game))
+                .run_if(resource_exists::<BossRush>())
+            )
+            .add_systems(OnExit(GameState::Game), end_boss_rush.run_if(resource_exists::<BossRush>()))
+        ;
+    }
+}
+
+/// Boss rush in progress, [GameData] isn't persisted while this resource exists
+#[derive(Resource, Default)]
+pub struct BossRush {
+    stage: usize,
+    /// Time spent fighting the current boss
+    timer: f32,
+    times: Vec<f32>,
+}
+
+impl BossRush {
+    /// Replace the game data with the boss rush loadout
+    pub fn start(commands: &mut Commands, player_life: &mut PlayerLife) {
+        let (_, spawner) = bosses::BOSS_RUSH[0];
+        commands.insert_resource(GameData::boss_rush(spawner));
+        commands.insert_resource(LevelManager::from_spawner(spawner.to_string()));
+        commands.insert_resource(BossRush::default());
+        player_life.reset(params::BOSS_RUSH_LIFE);
+    }
+}
+
+/// Best completion time of each boss rush stage, persisted apart from [GameData]
+#[derive(Serialize, Deserialize, Resource, Default, Debug)]
+pub struct BossRushRecords {
+    pub best: Vec<Option<f32>>,
+}
+
+pub fn format_time(time: Option<f32>) -> String {
+    match time {
+        Some(t) => format!("{:.2}s", t),
+        None => "--".to_string(),
+    }
+}
+
+fn init_records(
+    mut commands: Commands,
+    pkv: Res<PkvStore>,
+) {
+    let records = pkv.get::<BossRushRecords>(params::BOSS_RUSH_RECORDS_KEY).unwrap_or_default();
+    commands.insert_resource(records);
+}
+
+fn update_boss_rush(
+    mut commands: Commands,
+    mut rush: ResMut<BossRush>,
+    mut records: ResMut<BossRushRecords>,
+    data: Res<GameData>,
+    mut pkv: ResMut<PkvStore>,
+    mut transition: ResMut<ScreenTransition>,
+    cutscene: Option<Res<Cutscene>>,
+    time: Res<Time>,
+) {
+    let Some(&(boss, _)) = bosses::BOSS_RUSH.get(rush.stage) else {
+        // Back to the menu once the results have been shown, the screen is still covered
+        if cutscene.is_none() && transition.is_covered() {
+            *transition = ScreenTransition::to(GameState::Menu).covered();
+        }
+        return;
+    };
+
+    // Only the fight itself is timed
+    if !data.has_flag(boss.defeated) {
+        if data.has_flag(boss.intro) { rush.timer += time.raw_delta_seconds(); }
+        return;
+    }
+
+    // Boss defeated, record the time
+    let stage = rush.stage;
+    let stage_time = rush.timer;
+    rush.times.push(stage_time);
+    rush.stage += 1;
+    rush.timer = 0.0;
+
+    if records.best.len() <= stage { records.best.resize(stage + 1, None); }
+    if records.best[stage].map_or(true, |best| stage_time < best) {
+        records.best[stage] = Some(stage_time);
+        if pkv.set(params::BOSS_RUSH_RECORDS_KEY, records.as_ref()).is_err() {
+            error!("Couldn't persist boss rush records.");
+        }
+    }
+
+    let mut cutscene = VecDeque::from([
+        CSEvent::Wait(params::BOSS_RUSH_PAUSE),
+        CSEvent::fade_out(),
+        CSEvent::text_centered(format!("{}\n\n{}", boss.name, format_time(Some(stage_time)))),
+    ]);
+
+    match bosses::BOSS_RUSH.get(rush.stage) {
+        Some(&(_, spawner)) => cutscene.extend([
+            CSEvent::SetLife(params::BOSS_RUSH_LIFE),
+            CSEvent::Teleport(spawner.to_string()),
+            CSEvent::fade_in(),
+        ]),
+        None => {
+            // Rush completed, show the results before going back to the menu
+            let total: f32 = rush.times.iter().sum();
+            cutscene.push_back(CSEvent::text_centered(format!("Boss rush complete!\n\n{}", format_time(Some(total)))));
+        }
+    }
+    commands.insert_resource(Cutscene::from(&cutscene));
+}
+
+/// Escape leaves the boss rush for the menu
+fn quit_boss_rush(
+    input: Res<Input<KeyCode>>,
+    mut transition: ResMut<ScreenTransition>,
+    cutscene: Option<Res<Cutscene>>,
+    map: Option<Res<WorldMap>>,
+) {
+    if input.just_pressed(KeyCode::Escape) && cutscene.is_none() && map.is_none() && transition.is_none() {
+        *transition = ScreenTransition::to(GameState::Menu);
+    }
+}
+
+/// Bring back the story data once the boss rush is left
+fn end_boss_rush(
+    mut commands: Commands,
+    pkv: Res<PkvStore>,
+    mut player_life: ResMut<PlayerLife>,
+) {
+    let data = GameData::load(&pkv);
+    commands.insert_resource(LevelManager::from_spawner(data.last_spawner.clone()));
+    player_life.reset(data.max_life);
+    commands.insert_resource(data);
+    commands.remove_resource::<BossRush>();
+}
@@ -4,8 +4,8 @@ use bevy::utils::HashMap;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::definitions::colliders;
-use crate::entities::player::{Player, PlayerSize};
+use crate::entities::player::Player;
+use crate::entities::registry::entity_def;
 use crate::level_collision_data::{collision_data_from_image, LevelCollisionData};
 use crate::logic::attack::Sword;
 
@@ -61,60 +61,9 @@ impl Default for ColliderBundle {
 
 impl From<&EntityInstance> for ColliderBundle {
     fn from(value: &EntityInstance) -> Self {
-        let rotation_constraints = LockedAxes::ROTATION_LOCKED;
-
-        match value.identifier.as_ref() {
-            "Player" => ColliderBundle {
-                collider: Collider::from(PlayerSize::M),
-                rigid_body: RigidBody::KinematicPositionBased,
-                // friction: Friction {
-                //     coefficient: 0.0,
-                //     combine_rule: CoefficientCombineRule::Min,
-                // },
-                rotation_constraints,
-                controller: KinematicCharacterController {
-                    //max_slope_climb_angle: 0.0,
-                    autostep: Some(CharacterAutostep {
-                   //     max_height: CharacterLength::Relative(0.1),
-                        ..default()
-                    }),
-                    filter_flags: QueryFilterFlags::EXCLUDE_SENSORS,
-                    ..default()
-                },
-                ..Default::default()
-            },
-            "Zombie" => ColliderBundle {
-                collider: colliders::zombie(1),
-                rigid_body: RigidBody::Dynamic,
-                rotation_constraints,
-                ..default()
-            },
-            "Bird" => ColliderBundle {
-                collider: colliders::bird(),
-                rigid_body: RigidBody::KinematicVelocityBased,
-                ..default()
-            },
-            "Boss1" => ColliderBundle {
-                collider: colliders::boss1(3),
-                rigid_body: RigidBody::Fixed,
-                ..default()
-            },
-            "Boss2" => ColliderBundle {
-                collider: colliders::boss2(8),
-                rigid_body: RigidBody::Fixed,
-                ..default()
-            },
-            "Boss3" => ColliderBundle {
-                collider: colliders::boss3(),
-                rigid_body: RigidBody::KinematicPositionBased,
-                ..default()
-            },
-            "DamageZone" => ColliderBundle {
-                collider: Collider::cuboid(5., 5.),
-                rigid_body: RigidBody::Fixed,
-                ..default()
-            },
-            _ => ColliderBundle::default()
+        match entity_def(&value.identifier).and_then(|def| def.collider) {
+            Some(collider) => collider(),
+            None => ColliderBundle::default(),
         }
     }
 }
@@ -126,6 +75,11 @@ pub struct Hitbox;
 pub struct Damaged {
     pub entity: Entity,
     pub right_dir: bool,
+    pub damage: usize,
+    /// Multiplies the knockback of the entity hit
+    pub knockback: f32,
+    /// Heavy hits can hurt armored entities
+    pub heavy: bool,
 }
 
 pub fn collide_sword(
@@ -139,12 +93,15 @@ pub fn collide_sword(
 
     for (sword_e, mut sword) in sword.iter_mut() {
         for e in &hitbox {
-            if sword.0.contains(&e) { continue }
+            if sword.hit.contains(&e) { continue }
             if collisions.intersection_pair(sword_e, e).is_some() {
-                sword.0.push(e);
+                sword.hit.push(e);
                 damaged.send(Damaged {
                     entity: e,
                     right_dir: !player_sprite.flip_x,
+                    damage: sword.damage,
+                    knockback: sword.knockback,
+                    heavy: sword.heavy,
                 });
             }
         }
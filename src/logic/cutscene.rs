@@ -6,12 +6,12 @@ use crate::definitions::cutscenes;
 use crate::entities::animation::AnimStep;
 use crate::entities::player::Player;
 use crate::entities::player_sensor::PlayerEnteredSensorEvent;
-use crate::graphics::TextStyles;
+use crate::graphics::{ScreenTransition, TextStyles, TransitionEffect};
 use crate::logic::{GameData, LevelManager, PlayerLife};
 use crate::logic::data::Flags;
 use crate::music::{BGM, PlayBGMEvent};
 use crate::params;
-use crate::screens::{Fonts, Textures};
+use crate::screens::{Fonts, GameUI, Textures};
 
 #[derive(Clone, Debug)]
 pub enum CSEvent {
@@ -21,10 +21,10 @@ pub enum CSEvent {
     Text(String, f32, f32, f32),
     /// Show a text (top dy / left dx / timer)
     EternalText(String, f32, f32, f32),
-    /// Fade to black
-    FadeOut(f32, f32),
-    /// Fade from black
-    FadeIn(f32, f32),
+    /// Cover the screen with a [ScreenTransition] (effect / duration / started)
+    Cover(TransitionEffect, f32, bool),
+    /// Reveal the screen covered by [CSEvent::Cover] (effect / duration / started)
+    Reveal(TransitionEffect, f32, bool),
     /// Move the player to the given PlayerSpawner pos_id
     Teleport(String),
     /// Play a BGM
@@ -46,19 +46,25 @@ pub enum CSEvent {
 }
 
 impl CSEvent {
-    pub fn fade_in() -> Self { CSEvent::FadeIn(0.0, 1.0) }
-    pub fn fade_in_with_speed(speed: f32) -> Self { CSEvent::FadeIn(0.0, speed) }
-    pub fn fade_out() -> Self { CSEvent::FadeOut(0.0, 1.0) }
-    pub fn fade_out_with_speed(speed: f32) -> Self { CSEvent::FadeOut(0.0, speed) }
+    /// Fade from black, see [CSEvent::Reveal]
+    pub fn fade_in() -> Self { CSEvent::fade_in_with_speed(1.0) }
+    pub fn fade_in_with_speed(speed: f32) -> Self { CSEvent::Reveal(TransitionEffect::Fade, 1.0 / speed, false) }
+    /// Fade to black, see [CSEvent::Cover]
+    pub fn fade_out() -> Self { CSEvent::fade_out_with_speed(1.0) }
+    pub fn fade_out_with_speed(speed: f32) -> Self { CSEvent::Cover(TransitionEffect::Fade, 1.0 / speed, false) }
+    pub fn cover(effect: TransitionEffect) -> Self { CSEvent::Cover(effect, params::TRANSITION_TIME, false) }
+    pub fn cover_with_duration(effect: TransitionEffect, duration: f32) -> Self { CSEvent::Cover(effect, duration, false) }
+    pub fn reveal(effect: TransitionEffect) -> Self { CSEvent::Reveal(effect, params::TRANSITION_TIME, false) }
+    pub fn reveal_with_duration(effect: TransitionEffect, duration: f32) -> Self { CSEvent::Reveal(effect, duration, false) }
     pub fn text_centered(text: String) -> Self { CSEvent::Text(text, 0.0, 0.0, 0.0) }
     pub fn eternal_text(text: String) -> Self { CSEvent::EternalText(text, 0.0, 0.0, 0.0) }
     pub fn text_offset(text: String, top: f32, left: f32) -> Self { CSEvent::Text(text, top, left, 0.0) }
 
-    fn is_over(&self, input: &Input<KeyCode>) -> bool {
+    fn is_over(&self, input: &Input<KeyCode>, transition: &ScreenTransition) -> bool {
         match self {
             CSEvent::Wait(t) => input.just_pressed(KeyCode::Space) || *t <= 0.0,
-            CSEvent::FadeOut(t, speed) => input.just_pressed(KeyCode::Space) || *t * *speed >= 1.0,
-            CSEvent::FadeIn(t, speed) => input.just_pressed(KeyCode::Space) || *t * *speed >= 1.0,
+            CSEvent::Cover(_, _, started) => *started && (input.just_pressed(KeyCode::Space) || transition.is_covered()),
+            CSEvent::Reveal(_, _, started) => *started && (input.just_pressed(KeyCode::Space) || transition.is_none()),
             CSEvent::Text(txt, _, _, timer) => input.just_pressed(KeyCode::Space) || *timer >= (txt.len() as f32 * params::CHAR_DISPLAY_TIME + params::TEXT_FADE_TIME * 2.0),
             CSEvent::EternalText(..) => false,
             _ => true
@@ -76,6 +82,7 @@ impl Cutscene {
 #[derive(Component)]
 pub struct Cinema;
 
+/// Screen faded in and out by [TransitionEffect::Fade] transitions
 #[derive(Component)]
 pub struct Frame;
 
@@ -90,6 +97,7 @@ pub fn init(
     textures: Res<Textures>,
     fonts: Res<Fonts>,
     data: Res<GameData>,
+    mut transition: ResMut<ScreenTransition>,
 ) {
     let absolute = Style {
         position_type: PositionType::Absolute,
@@ -107,6 +115,7 @@ pub fn init(
             ..default()
         })
         .insert(Cinema)
+        .insert(GameUI)
     ;
 
     commands
@@ -121,6 +130,7 @@ pub fn init(
             ..default()
         })
         .insert(CutsceneText)
+        .insert(GameUI)
     ;
 
     commands
@@ -135,12 +145,15 @@ pub fn init(
             ..default()
         })
         .insert(CutsceneText2)
+        .insert(GameUI)
     ;
 
     let initial_cutscene = !data.has_flag(Flags::Intro);
 
+    // The intro starts on a black screen
     if initial_cutscene {
         commands.insert_resource(Cutscene::from(&cutscenes::INTRO));
+        *transition = ScreenTransition::cover().with(TransitionEffect::Fade, 0.0);
     }
 
     commands
@@ -152,6 +165,7 @@ pub fn init(
             ..default()
         })
         .insert(Frame)
+        .insert(GameUI)
     ;
 }
 
@@ -161,7 +175,6 @@ pub fn update(
     mut cinema: Query<(&mut Visibility, &mut BackgroundColor), With<Cinema>>,
     mut time: ResMut<Time>,
     mut bgm: EventWriter<PlayBGMEvent>,
-    mut frame: Query<&mut BackgroundColor, (With<Frame>, Without<Cinema>)>,
     mut level_manager: ResMut<LevelManager>,
     mut text: Query<(&mut Text, &mut Style), With<CutsceneText>>,
     mut text2: Query<(&mut Text, &mut Style), (With<CutsceneText2>, Without<CutsceneText>)>,
@@ -170,6 +183,7 @@ pub fn update(
     mut player: Query<&mut AnimStep, With<Player>>,
     mut data: ResMut<GameData>,
     mut player_life: ResMut<PlayerLife>,
+    mut transition: ResMut<ScreenTransition>,
 ) {
     let Ok((mut cin_vis, mut cin_col)) = cinema.get_single_mut() else { return };
     let Some(mut cutscene) = cutscene else {
@@ -249,17 +263,19 @@ pub fn update(
                 );
             }
         }
-        CSEvent::FadeOut(t, speed) => {
-            *t += time.delta_seconds();
-            if let Ok(mut bg) = frame.get_single_mut() { bg.0.set_a(
-                if input.just_pressed(KeyCode::Space) { 1.0 } else { (*t * *speed).powi(3).min(1.0) }
-            ); }
+        CSEvent::Cover(effect, duration, started) => {
+            if !*started {
+                *transition = ScreenTransition::cover().with(*effect, *duration);
+                *started = true;
+            }
+            if input.just_pressed(KeyCode::Space) { transition.skip(); }
         }
-        CSEvent::FadeIn(t, speed) => {
-            *t += time.delta_seconds();
-            if let Ok(mut bg) = frame.get_single_mut() { bg.0.set_a(
-                if input.just_pressed(KeyCode::Space) { 0.0 } else { (1.0 - (*t * *speed).powi(3)).max(0.0) }
-            ); }
+        CSEvent::Reveal(effect, duration, started) => {
+            if !*started {
+                *transition = ScreenTransition::reveal().with(*effect, *duration);
+                *started = true;
+            }
+            if input.just_pressed(KeyCode::Space) { transition.skip(); }
         }
         CSEvent::ToggleCinema(show) => {
             cin_vis.set_if_neq(if *show { Visibility::Inherited } else { Visibility::Hidden });
@@ -277,7 +293,7 @@ pub fn update(
     }
 
     // Go to next event
-    if event.is_over(&input) {
+    if event.is_over(&input, &transition) {
         cutscene.0.pop_front();
         if cutscene.0.is_empty() {
             commands.remove_resource::<Cutscene>();
@@ -313,6 +329,11 @@ pub fn trigger_cutscene(
                         commands.insert_resource(Cutscene::from(&cutscenes::BOSS_1));
                     }
                 }
+                "boss2" => {
+                    if !game_data.has_flag(Flags::Boss2Start) && !game_data.has_flag(Flags::Boss2Defeated) {
+                        commands.insert_resource(Cutscene::from(&cutscenes::BOSS_2));
+                    }
+                }
                 "boss3" => {
                     if !game_data.has_flag(Flags::Boss3Start) {
                         commands.insert_resource(Cutscene::from(&cutscenes::BOSS_3));
@@ -1,12 +1,12 @@
 use bevy::input::Input;
-use bevy::prelude::{DetectChanges, KeyCode, Res, ResMut, Resource};
-use bevy::utils::hashbrown::HashSet;
+use bevy::prelude::{Component, DetectChanges, KeyCode, Res, ResMut, Resource};
+use bevy::utils::hashbrown::{HashMap, HashSet};
 use bevy_pkv::PkvStore;
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 
-use crate::logic::{LevelManager, PlayerLife};
+use crate::logic::{BossRush, LevelManager, PlayerLife};
 use crate::params;
 
 /// Contain persisted game data.
@@ -16,6 +16,29 @@ pub struct GameData {
     pub last_spawner: String,
     pub max_life: usize,
     pub removed_named: HashSet<String>,
+    /// Killed enemies that don't respawn with the level, by LDtk iid
+    #[serde(default)]
+    pub killed: HashMap<String, Respawn>,
+    /// Levels entered at least once, by LDtk iid
+    #[serde(default)]
+    pub visited: HashSet<String>,
+    /// Chunks revealed on the minimap, see [params::EXPLORE_CHUNK]
+    #[serde(default)]
+    pub explored: HashSet<(i32, i32)>,
+    /// Fast travel destinations, by spawner iid in activation order
+    #[serde(default)]
+    pub checkpoints: Vec<String>,
+}
+
+/// When a killed enemy comes back, set with the `Respawn` LDtk field
+#[derive(Serialize, Deserialize, Component, Copy, Clone, Eq, PartialEq, Debug, Default, EnumString)]
+pub enum Respawn {
+    /// Each time the level is loaded
+    #[default]
+    Always,
+    /// When the player rests at a checkpoint
+    OnRest,
+    Never,
 }
 
 impl Default for GameData {
@@ -25,6 +48,10 @@ impl Default for GameData {
             last_spawner: params::INITIAL_SPAWNER_ID.to_string(),
             max_life: params::STARTING_LIFE,
             removed_named: HashSet::new(),
+            killed: HashMap::new(),
+            visited: HashSet::new(),
+            explored: HashSet::new(),
+            checkpoints: vec![],
         }
     }
 }
@@ -42,6 +69,30 @@ impl GameData {
     pub fn has_flag(&self, flag: Flags) -> bool {
         self.flags.contains(&flag)
     }
+
+    /// Persisted story data, or a new game
+    pub fn load(pkv: &PkvStore) -> Self {
+        pkv.get::<GameData>(params::GAME_DATA_KEY).unwrap_or_default()
+    }
+
+    /// Loadout of the boss rush: every size, the dash and a fixed number of hearts
+    pub fn boss_rush(spawner: &str) -> Self {
+        GameData {
+            flags: HashSet::from_iter([Flags::Intro, Flags::SizeS, Flags::SizeL, Flags::Dash]),
+            last_spawner: spawner.to_string(),
+            max_life: params::BOSS_RUSH_LIFE,
+            removed_named: HashSet::new(),
+            killed: HashMap::new(),
+            visited: HashSet::new(),
+            explored: HashSet::new(),
+            checkpoints: vec![],
+        }
+    }
+
+    /// Bring back the enemies killed since the last rest
+    pub fn rest(&mut self) {
+        self.killed.retain(|_, respawn| *respawn == Respawn::Never);
+    }
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Hash, EnumString)]
@@ -81,7 +132,11 @@ pub fn save(
     mut pkv: ResMut<PkvStore>,
     player_life: Res<PlayerLife>,
     level_manager: Res<LevelManager>,
+    boss_rush: Option<Res<BossRush>>,
 ) {
+    // Boss rush data is never persisted
+    if boss_rush.is_some() { return; }
+
     if level_manager.is_changed() {
         data.last_spawner = level_manager.spawner_id().clone();
     }
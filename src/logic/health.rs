@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::ldtk::FieldInstance;
+
+use crate::graphics::Hurt;
+use crate::logic::Damaged;
+use crate::params;
+use crate::util::{get_ldtk_field_bool, get_ldtk_field_float, get_ldtk_field_int};
+
+/// Life points of an enemy or a boss part, lowered by [Damaged] events
+#[derive(Component, Copy, Clone, Debug)]
+pub struct Health {
+    pub hp: usize,
+    pub max: usize,
+    /// Hits are ignored for this long after being hurt, the entity blinks meanwhile
+    pub iframes: f32,
+    /// Part of the knockback ignored, from 0 to 1
+    pub knockback_resistance: f32,
+    /// Only hurt by heavy hits
+    pub armored: bool,
+    invulnerable: f32,
+    died: bool,
+}
+
+impl Health {
+    pub const fn new(hp: usize) -> Self {
+        Health {
+            hp,
+            max: hp,
+            iframes: params::ENEMY_HURT_TIME,
+            knockback_resistance: 0.0,
+            armored: false,
+            invulnerable: 0.0,
+            died: false,
+        }
+    }
+
+    pub const fn with_knockback_resistance(mut self, resistance: f32) -> Self {
+        self.knockback_resistance = resistance;
+        self
+    }
+
+    pub const fn armored(mut self) -> Self {
+        self.armored = true;
+        self
+    }
+
+    /// Override the default stats with the LDtk fields of the instance
+    pub fn with_fields(mut self, fields: &Vec<FieldInstance>) -> Self {
+        if let Some(hp) = get_ldtk_field_int(fields, "HP") {
+            self.hp = hp;
+            self.max = hp;
+        }
+        if let Some(resistance) = get_ldtk_field_float(fields, "KnockbackResistance") { self.knockback_resistance = resistance; }
+        if let Some(armored) = get_ldtk_field_bool(fields, "Armored") { self.armored = armored; }
+        self
+    }
+
+    pub fn dead(&self) -> bool {
+        self.hp == 0
+    }
+
+    pub fn invulnerable(&self) -> bool {
+        self.invulnerable > 0.0
+    }
+
+    pub fn heal(&mut self) {
+        self.hp = self.max;
+        self.died = false;
+    }
+
+    /// Speed of a knockback once the resistance is applied
+    pub fn knockback(&self, speed: f32) -> f32 {
+        speed * (1.0 - self.knockback_resistance).clamp(0.0, 1.0)
+    }
+}
+
+/// Sent when a [Damaged] event lowered the [Health] of an entity
+#[derive(Event)]
+pub struct Wounded {
+    pub entity: Entity,
+    pub right_dir: bool,
+    pub damage: usize,
+    pub knockback: f32,
+}
+
+/// Sent once the [Health] of an entity reached zero and the killing blow's blink is over
+#[derive(Event)]
+pub struct Died {
+    pub entity: Entity,
+}
+
+pub fn apply_damage(
+    mut commands: Commands,
+    mut healths: Query<(Entity, &mut Health)>,
+    mut damaged: EventReader<Damaged>,
+    mut wounded: EventWriter<Wounded>,
+    mut died: EventWriter<Died>,
+    time: Res<Time>,
+) {
+    for (e, mut health) in healths.iter_mut() {
+        health.invulnerable = (health.invulnerable - time.delta_seconds()).max(0.0);
+        if health.dead() && !health.invulnerable() && !health.died {
+            health.died = true;
+            died.send(Died { entity: e });
+        }
+    }
+
+    for Damaged { entity, right_dir, damage, knockback, heavy } in damaged.iter() {
+        let Ok((_, mut health)) = healths.get_mut(*entity) else { continue };
+        if health.dead() || health.invulnerable() { continue }
+        if health.armored && !heavy { continue }
+
+        health.hp = health.hp.saturating_sub(*damage);
+        health.invulnerable = health.iframes;
+        if health.iframes > 0.0 {
+            commands.entity(*entity).insert(Hurt::new(health.iframes));
+        }
+        wounded.send(Wounded { entity: *entity, right_dir: *right_dir, damage: *damage, knockback: *knockback });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_damage(world: &mut World, damage: Damaged) {
+        world.resource_mut::<Events<Damaged>>().send(damage);
+        let mut schedule = Schedule::default();
+        schedule.add_systems(apply_damage);
+        schedule.run(world);
+    }
+
+    fn world_with(health: Health) -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Events<Damaged>>();
+        world.init_resource::<Events<Wounded>>();
+        world.init_resource::<Events<Died>>();
+        let e = world.spawn(health).id();
+        (world, e)
+    }
+
+    fn hit(entity: Entity, damage: usize, heavy: bool) -> Damaged {
+        Damaged { entity, right_dir: true, damage, knockback: 1.0, heavy }
+    }
+
+    #[test]
+    fn knockback_ignores_the_resistance_part() {
+        assert_eq!(Health::new(3).knockback(100.0), 100.0);
+        assert_eq!(Health::new(3).with_knockback_resistance(0.25).knockback(100.0), 75.0);
+        assert_eq!(Health::new(3).with_knockback_resistance(1.5).knockback(100.0), 0.0);
+        assert_eq!(Health::new(3).with_knockback_resistance(-0.5).knockback(100.0), 100.0);
+    }
+
+    #[test]
+    fn heal_restores_max() {
+        let mut health = Health::new(3);
+        health.hp = 0;
+        assert!(health.dead());
+        health.heal();
+        assert_eq!(health.hp, 3);
+        assert!(!health.dead());
+    }
+
+    #[test]
+    fn hits_are_ignored_while_invulnerable() {
+        let (mut world, e) = world_with(Health::new(5));
+        run_damage(&mut world, hit(e, 2, false));
+        run_damage(&mut world, hit(e, 2, false));
+
+        assert_eq!(world.get::<Health>(e).unwrap().hp, 3);
+        assert!(world.get::<Hurt>(e).is_some());
+        assert_eq!(world.resource::<Events<Wounded>>().len(), 1);
+    }
+
+    #[test]
+    fn armored_only_hurt_by_heavy_hits() {
+        let (mut world, e) = world_with(Health::new(5).armored());
+        run_damage(&mut world, hit(e, 2, false));
+        assert_eq!(world.get::<Health>(e).unwrap().hp, 5);
+
+        run_damage(&mut world, hit(e, 2, true));
+        assert_eq!(world.get::<Health>(e).unwrap().hp, 3);
+    }
+
+    #[test]
+    fn dies_once() {
+        let (mut world, e) = world_with(Health { iframes: 0.0, ..Health::new(1) });
+        run_damage(&mut world, hit(e, 3, false));
+        assert_eq!(world.get::<Health>(e).unwrap().hp, 0);
+        assert_eq!(world.resource::<Events<Died>>().len(), 0);
+
+        run_damage(&mut world, hit(e, 1, false));
+        run_damage(&mut world, hit(e, 1, false));
+        assert_eq!(world.resource::<Events<Wounded>>().len(), 1);
+        assert_eq!(world.resource::<Events<Died>>().len(), 1);
+    }
+}
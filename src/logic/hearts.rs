@@ -6,7 +6,7 @@ use crate::entities::NamedEntity;
 use crate::entities::player_sensor::PlayerEnteredSensorEvent;
 use crate::logic::{Cutscene, Flags, GameData, Vanish};
 use crate::music::{PlaySFXEvent, SFX};
-use crate::screens::ScreenShake;
+use crate::screens::{GameUI, ScreenShake};
 
 pub struct HeartsPlugin;
 
@@ -34,19 +34,29 @@ pub struct PlayerLife {
 impl PlayerLife {
     pub fn max_life(&self) -> usize { self.max }
 
-    pub fn lose(&mut self) {
-        self.current = self.current.saturating_sub(1);
+    pub fn lose(&mut self, amount: usize) {
+        self.current = self.current.saturating_sub(amount);
     }
 
     pub fn gain(&mut self) {
         self.current = (self.current + 1).min(self.max);
     }
 
+    pub fn raise_max(&mut self, amount: usize) {
+        self.max += amount;
+    }
+
     pub fn heal(&mut self) {
         self.current = self.max;
     }
 
     pub fn set_current(&mut self, to: usize) { self.current = to; }
+
+    /// Replace the hearts of the player, used by the boss rush loadout
+    pub fn reset(&mut self, max: usize) {
+        self.max = max;
+        self.current = max;
+    }
 }
 
 fn init_life(
@@ -75,8 +85,12 @@ fn die(
 #[derive(Component)]
 struct Heart(usize);
 
+/// Life shown by the hearts, drained one point at a time after a big hit
 #[derive(Component)]
-struct HeartsHolder;
+struct HeartsHolder {
+    displayed: usize,
+    timer: f32,
+}
 
 fn init_hearts_holder(
     mut commands: Commands,
@@ -89,7 +103,8 @@ fn init_hearts_holder(
             },
             ..default()
         })
-        .insert(HeartsHolder)
+        .insert(HeartsHolder { displayed: 0, timer: 0.0 })
+        .insert(GameUI)
     ;
 }
 
@@ -97,19 +112,42 @@ fn update_hearts(
     mut commands: Commands,
     textures: Res<Textures>,
     player_life: Res<PlayerLife>,
-    hearts_holder: Query<Entity, With<HeartsHolder>>,
-    mut hearts: Query<(&Heart, &mut UiTextureAtlasImage)>,
+    mut hearts_holder: Query<(Entity, &mut HeartsHolder)>,
+    mut hearts: Query<(&Heart, &mut UiTextureAtlasImage, &mut BackgroundColor)>,
+    time: Res<Time>,
 ) {
-    let hearts_holder = hearts_holder.single();
+    let (hearts_holder, mut holder) = hearts_holder.single_mut();
+
+    // Losses are shown one point at a time, gains are instant
+    if holder.displayed > player_life.current {
+        holder.timer += time.delta_seconds();
+        if holder.timer >= params::HEART_LOSS_STEP {
+            holder.timer = 0.0;
+            holder.displayed -= 1;
+        }
+    } else {
+        holder.displayed = player_life.current;
+        holder.timer = 0.0;
+    }
+    let displayed = holder.displayed;
+    let draining = displayed > player_life.current;
+
     let mut current_hearts = 0;
-    for (&Heart(idx), mut image) in hearts.iter_mut() {
+    for (&Heart(idx), mut image, mut color) in hearts.iter_mut() {
         image.index =
-            if player_life.current <= 2 * idx { 2 }
-            else { match player_life.current - 2 * idx {
+            if displayed <= 2 * idx { 2 }
+            else { match displayed - 2 * idx {
                 0 => 2,
                 1 => 1,
                 _ => 0,
             } };
+
+        // Blink the heart being drained
+        let blink = draining
+            && (displayed - 1) / 2 == idx
+            && holder.timer % (2.0 * params::HEART_BLINK) < params::HEART_BLINK;
+        color.0 = if blink { Color::rgba(1.0, 1.0, 1.0, 0.4) } else { Color::WHITE };
+
         current_hearts += 1;
     }
 
@@ -148,7 +186,7 @@ fn collect_new_heart(
             for (entity, NamedEntity(heart_name)) in heart_images.iter() {
                 if target_heart_name == heart_name && game_data.removed_named.insert(heart_name.clone()) {
                     commands.entity(entity).insert(Vanish::new(0.1));
-                    life.max += 2;
+                    life.raise_max(2);
                     life.heal();
                     sfx.send(PlaySFXEvent(SFX::NewHeart));
                     break;
@@ -1,13 +1,20 @@
+use std::str::FromStr;
+
 use bevy::{app::App, utils::HashSet};
 use bevy::prelude::*;
 use bevy_ecs_ldtk::{LevelIid, LevelSet, prelude::LdtkProject};
 use bevy_ecs_ldtk::{Respawn, Worldly};
+use bevy_ecs_ldtk::ldtk::{FieldInstance, FieldValue};
 
 use crate::entities::player::Player;
 use crate::entities::spawner::{SpawnerInfo, SpawnPlayer};
 use crate::GameState;
 use crate::logic::{Flags, GameData};
 use crate::music::{BGM, PlayBGMEvent};
+use crate::screens::CameraController;
+use crate::graphics::Ambient;
+use crate::graphics::parallax::ParallaxLayer;
+use crate::util::{get_ldtk_field_float, get_ldtk_field_string, get_ldtk_field_strings};
 
 #[derive(Debug, Event)]
 pub struct LevelUnloadedEvent(pub LevelIid);
@@ -29,18 +36,69 @@ impl Plugin for LevelLoadingPlugin {
     }
 }
 
+/// Point of interest shown on the world map
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MapMarker {
+    /// Checkpoint, with the iid of its spawner
+    Checkpoint(String),
+    Boss,
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct LevelOutline {
     pos: Vec2,
     size: Vec2,
     iid: String,
     id: String,
+    markers: Vec<(MapMarker, Vec2)>,
+    /// Camera zoom bounds, from the `MinZoom` and `MaxZoom` LDtk fields
+    zoom: (Option<f32>, Option<f32>),
+    ambient: Vec<Ambient>,
+    /// From the `Parallax` LDtk field
+    parallax: Vec<ParallaxLayer>,
+    /// Opacity of the darkness overlay, from the `Darkness` LDtk field
+    darkness: Option<f32>,
 }
 
 impl LevelOutline {
     pub fn rect(&self) -> Rect {
         Rect::from_corners(self.pos, self.pos + self.size)
     }
+
+    pub fn level_iid(&self) -> LevelIid {
+        LevelIid::new(self.iid.clone())
+    }
+
+    pub fn iid(&self) -> &String {
+        &self.iid
+    }
+
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    /// Clamp the camera zoom to the bounds of the level
+    pub fn clamp_zoom(&self, zoom: f32) -> f32 {
+        let (min, max) = self.zoom;
+        zoom.max(min.unwrap_or(f32::MIN)).min(max.unwrap_or(f32::MAX))
+    }
+
+    pub fn ambient(&self) -> &Vec<Ambient> {
+        &self.ambient
+    }
+
+    pub fn parallax(&self) -> &Vec<ParallaxLayer> {
+        &self.parallax
+    }
+
+    pub fn darkness(&self) -> Option<f32> {
+        self.darkness
+    }
+
+    /// Checkpoints and boss rooms of the level, in world coordinates
+    pub fn markers(&self) -> &Vec<(MapMarker, Vec2)> {
+        &self.markers
+    }
 }
 
 #[derive(Default, Resource, Debug)]
@@ -92,6 +150,18 @@ impl LevelManager {
             .iid
     }
 
+    pub fn spawner_id_of(&self, spawner_iid: &String) -> Option<&String> {
+        self.spawners.iter().find(|s| s.iid == *spawner_iid).map(|s| &s.id)
+    }
+
+    /// World position of the checkpoint using the given spawner
+    pub fn checkpoint_pos(&self, spawner_iid: &String) -> Option<Vec2> {
+        self.levels.iter()
+            .flat_map(|level| level.markers.iter())
+            .find(|(marker, _)| *marker == MapMarker::Checkpoint(spawner_iid.clone()))
+            .map(|(_, pos)| *pos)
+    }
+
     pub fn spawner_id(&self) -> &String {
         &self.spawner_id
     }
@@ -112,6 +182,14 @@ impl LevelManager {
         self.determine_level(&self.spawner_id)
     }
 
+    pub fn levels(&self) -> &Vec<LevelOutline> {
+        &self.levels
+    }
+
+    pub fn level_at(&self, pos: Vec2) -> Option<&LevelOutline> {
+        self.levels.iter().find(|level| level.rect().contains(pos))
+    }
+
     pub fn is_vec_inside_any_level(&self, pos: Vec2) -> bool {
         self.levels.iter().any(|level| {
             level.rect().contains(pos)
@@ -134,11 +212,50 @@ fn init_level_outlines(
 
     ldtk_project.as_standalone()
         .iter_loaded_levels()
-        .map(|ll| LevelOutline {
-            pos: Vec2::new(*ll.world_x() as f32, -*ll.world_y() as f32),
-            size: Vec2::new(*ll.px_wid() as f32, -*ll.px_hei() as f32),
-            iid: ll.iid().clone(),
-            id: ll.identifier().clone(),
+        .map(|ll| {
+            let pos = Vec2::new(*ll.world_x() as f32, -*ll.world_y() as f32);
+            let markers = ll.layer_instances()
+                .iter()
+                .flat_map(|layer| layer.entity_instances.iter())
+                .filter_map(|entity| {
+                    let marker = match entity.identifier.as_str() {
+                        "Checkpoint" => match entity.field_instances.iter().find(|f| f.identifier == "spawner") {
+                            Some(FieldInstance { value: FieldValue::EntityRef(Some(spawner)), .. }) => MapMarker::Checkpoint(spawner.entity_iid.clone()),
+                            _ => return None,
+                        },
+                        "Boss1" | "Boss2" | "Boss3" => MapMarker::Boss,
+                        _ => return None,
+                    };
+                    Some((marker, pos + Vec2::new(entity.px.x as f32, -entity.px.y as f32)))
+                })
+                .collect();
+
+            LevelOutline {
+                pos,
+                size: Vec2::new(*ll.px_wid() as f32, -*ll.px_hei() as f32),
+                iid: ll.iid().clone(),
+                id: ll.identifier().clone(),
+                markers,
+                zoom: (
+                    get_ldtk_field_float(ll.field_instances(), "MinZoom"),
+                    get_ldtk_field_float(ll.field_instances(), "MaxZoom"),
+                ),
+                ambient: get_ldtk_field_string(ll.field_instances(), "Ambient")
+                    .map(|ambient| ambient
+                        .split(',')
+                        .filter_map(|name| Ambient::from_str(name.trim())
+                            .map_err(|_| error!("Unknown ambient effect: {}", name))
+                            .ok())
+                        .collect())
+                    .unwrap_or_default(),
+                parallax: get_ldtk_field_strings(ll.field_instances(), "Parallax")
+                    .iter()
+                    .filter_map(|layer| ParallaxLayer::from_str(layer)
+                        .map_err(|_| error!("Bad parallax layer: {}", layer))
+                        .ok())
+                    .collect(),
+                darkness: get_ldtk_field_float(ll.field_instances(), "Darkness").filter(|darkness| *darkness > 0.0),
+            }
         })
         .for_each(|lo| level_manager.levels.push(lo));
 }
@@ -192,7 +309,7 @@ fn determine_loaded_levels(
     mut level_set: Query<&mut LevelSet>,
     level_manager: ResMut<LevelManager>,
     player_pos: Query<&Transform, With<Player>>,
-    camera: Query<(&Camera, &GlobalTransform)>,
+    camera: Query<(&Camera, &GlobalTransform), With<CameraController>>,
     mut unload_event_sink: EventWriter<LevelUnloadedEvent>,
 ) {
     let Ok(mut current_level_set) = level_set.get_single_mut() else { return };
@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::{GameState, params};
+use crate::entities::player::Player;
+use crate::logic::{GameData, LevelManager};
+use crate::screens::GameUI;
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(GameState::Game), init_minimap)
+            .add_systems(Update, (explore, update_minimap).chain().run_if(in_state(GameState::Game)))
+        ;
+    }
+}
+
+/// Corner map centered on the player, only the explored chunks are drawn
+#[derive(Component)]
+struct Minimap;
+
+/// Explored chunk, see [params::EXPLORE_CHUNK]
+#[derive(Component)]
+struct MinimapChunk((i32, i32));
+
+/// Outline of a visited level
+#[derive(Component)]
+struct MinimapOutline(String, Rect);
+
+fn init_minimap(
+    mut commands: Commands,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(params::MINIMAP_MARGIN),
+                right: Val::Px(params::MINIMAP_MARGIN),
+                width: Val::Px(params::MINIMAP_SIZE.0),
+                height: Val::Px(params::MINIMAP_SIZE.1),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.5)),
+            z_index: ZIndex::Global(params::ui_z::MINIMAP),
+            ..default()
+        })
+        .insert(Minimap)
+        .insert(GameUI)
+        .with_children(|builder| {
+            let size = 4.0;
+            builder.spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px((params::MINIMAP_SIZE.0 - size) / 2.),
+                    top: Val::Px((params::MINIMAP_SIZE.1 - size) / 2.),
+                    width: Val::Px(size),
+                    height: Val::Px(size),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::WHITE),
+                // Above the chunks
+                z_index: ZIndex::Global(params::ui_z::MINIMAP + 1),
+                ..default()
+            });
+        })
+    ;
+}
+
+fn chunk_of(pos: Vec2) -> (i32, i32) {
+    (
+        (pos.x / params::EXPLORE_CHUNK).floor() as i32,
+        (pos.y / params::EXPLORE_CHUNK).floor() as i32,
+    )
+}
+
+/// Reveal the chunks around the player
+fn explore(
+    player: Query<&Transform, With<Player>>,
+    level_manager: Res<LevelManager>,
+    mut data: ResMut<GameData>,
+) {
+    let Ok(pos) = player.get_single() else { return };
+    let (x, y) = chunk_of(pos.translation.truncate());
+
+    let r = params::EXPLORE_RADIUS;
+    for dx in -r..=r {
+        for dy in -r..=r {
+            if dx * dx + dy * dy > r * r { continue }
+            let chunk = (x + dx, y + dy);
+            if data.explored.contains(&chunk) { continue }
+
+            // Chunks outside of the levels are never drawn
+            let center = (Vec2::new(chunk.0 as f32, chunk.1 as f32) + 0.5) * params::EXPLORE_CHUNK;
+            if !level_manager.is_vec_inside_any_level(center) { continue }
+            data.explored.insert(chunk);
+        }
+    }
+}
+
+fn update_minimap(
+    mut commands: Commands,
+    data: Res<GameData>,
+    level_manager: Res<LevelManager>,
+    player: Query<&Transform, With<Player>>,
+    minimap: Query<Entity, With<Minimap>>,
+    mut chunks: Query<(Entity, &MinimapChunk, &mut Style), Without<MinimapOutline>>,
+    mut outlines: Query<(Entity, &MinimapOutline, &mut Style), Without<MinimapChunk>>,
+) {
+    let Ok(minimap) = minimap.get_single() else { return };
+    let Ok(player) = player.get_single() else { return };
+    let player = player.translation.truncate();
+
+    // New chunks and levels are added, the ones forgotten by a reset are removed
+    if data.is_changed() {
+        let mut shown = HashSet::new();
+        for (e, MinimapChunk(chunk), _) in chunks.iter() {
+            if data.explored.contains(chunk) { shown.insert(*chunk); }
+            else { commands.entity(e).despawn_recursive(); }
+        }
+        for &chunk in data.explored.iter().filter(|chunk| !shown.contains(*chunk)) {
+            commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(params::EXPLORE_CHUNK * params::MINIMAP_ZOOM),
+                        height: Val::Px(params::EXPLORE_CHUNK * params::MINIMAP_ZOOM),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::rgb(0.3, 0.3, 0.4)),
+                    z_index: ZIndex::Global(params::ui_z::MINIMAP),
+                    ..default()
+                })
+                .insert(MinimapChunk(chunk))
+                .set_parent(minimap)
+            ;
+        }
+
+        let mut shown = HashSet::new();
+        for (e, MinimapOutline(iid, _), _) in outlines.iter() {
+            if data.visited.contains(iid) { shown.insert(iid.clone()); }
+            else { commands.entity(e).despawn_recursive(); }
+        }
+        for level in level_manager.levels() {
+            if !data.visited.contains(level.iid()) || shown.contains(level.iid()) { continue }
+            commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(level.rect().width() * params::MINIMAP_ZOOM),
+                        height: Val::Px(level.rect().height() * params::MINIMAP_ZOOM),
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::rgb(0.8, 0.8, 0.9)),
+                    z_index: ZIndex::Global(params::ui_z::MINIMAP),
+                    ..default()
+                })
+                .insert(MinimapOutline(level.iid().clone(), level.rect()))
+                .set_parent(minimap)
+            ;
+        }
+    }
+
+    // UI coordinates go down
+    let to_minimap = |pos: Vec2| Vec2::new(
+        params::MINIMAP_SIZE.0 / 2. + (pos.x - player.x) * params::MINIMAP_ZOOM,
+        params::MINIMAP_SIZE.1 / 2. - (pos.y - player.y) * params::MINIMAP_ZOOM,
+    );
+
+    for (_, MinimapChunk((x, y)), mut style) in chunks.iter_mut() {
+        let top_left = to_minimap(Vec2::new(*x as f32, (*y + 1) as f32) * params::EXPLORE_CHUNK);
+        style.left = Val::Px(top_left.x);
+        style.top = Val::Px(top_left.y);
+    }
+
+    for (_, MinimapOutline(_, rect), mut style) in outlines.iter_mut() {
+        let top_left = to_minimap(Vec2::new(rect.min.x, rect.max.y));
+        style.left = Val::Px(top_left.x);
+        style.top = Val::Px(top_left.y);
+    }
+}
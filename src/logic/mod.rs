@@ -2,20 +2,27 @@ use bevy::app::App;
 use bevy::prelude::*;
 use bevy_pkv::PkvStore;
 
+pub use attack::{AttackDir, Combo, Pogo, Sword};
+pub use boss_rush::{BossRush, BossRushRecords, format_time};
 pub use collision::{ColliderBundle, Damaged, Hitbox, LevelColliderGroup};
 pub use cutscene::CSEvent;
-pub use cutscene::Cutscene;
-pub use data::{Flags, GameData};
+pub use cutscene::{Cutscene, Frame};
+pub use data::{Flags, GameData, Respawn};
+pub use health::{apply_damage, Died, Health, Wounded};
 pub use hearts::PlayerLife;
 pub use hit_stop::HitStop;
 pub use knockback::Knockback;
 pub use level_loading::*;
 pub use movement::move_player;
 pub use vanish::Vanish;
+pub use world_map::WorldMap;
 
 use crate::{entities::zombie::patrol_zombie, GameState, params};
 
 mod hearts;
+mod health;
+mod boss_bar;
+mod boss_rush;
 mod collision;
 mod movement;
 mod level_loading;
@@ -26,6 +33,8 @@ mod cutscene;
 mod data;
 mod vanish;
 mod swords_disappear;
+mod world_map;
+mod minimap;
 
 pub struct LogicPlugin;
 
@@ -36,18 +45,26 @@ impl Plugin for LogicPlugin {
             .add_plugins(LevelLoadingPlugin)
             .add_plugins(collision::CollisionPlugin)
             .add_plugins(hearts::HeartsPlugin)
+            .add_plugins(boss_bar::BossBarPlugin)
+            .add_plugins(boss_rush::BossRushPlugin)
+            .add_plugins(world_map::WorldMapPlugin)
+            .add_plugins(minimap::MinimapPlugin)
             .add_event::<attack::SpawnSword>()
+            .add_event::<Wounded>()
+            .add_event::<Died>()
             .add_systems(Startup, (init_logic))
             .add_systems(Update, (vanish::update_vanish, movement::collect_dash, swords_disappear::make_swords_disappear))
             .add_systems(Update, (data::save, data::reset))
-            .add_systems(Update, (movement::move_player, attack::attack, attack::update_sword)
+            .add_systems(Update, (movement::move_player, attack::attack, attack::update_sword, attack::pogo.after(collision::collide_sword))
                 .run_if(not(resource_exists::<Cutscene>()))
+                .run_if(not(resource_exists::<WorldMap>()))
             )
             .add_systems(Update,
                 (
                     (knockback::process_knockback, hit_stop::process_hit_stop).chain()
                         .after(movement::move_player)
                         .after(patrol_zombie),
+                    health::apply_damage.after(collision::collide_sword),
                 ).run_if(in_state(GameState::Game))
             )
             .add_systems(OnEnter(GameState::Game), (cutscene::init))
@@ -60,12 +77,9 @@ impl Plugin for LogicPlugin {
 
 fn init_logic(
     mut commands: Commands,
-    mut pkv: ResMut<PkvStore>,
+    pkv: Res<PkvStore>,
 ) {
-    let data = match pkv.get::<GameData>(params::GAME_DATA_KEY) {
-        Ok(data) => data,
-        Err(_) => GameData::default()
-    };
+    let data = GameData::load(&pkv);
 
     // info!("Game data: {:?}", data);
 
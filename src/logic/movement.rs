@@ -7,7 +7,7 @@ use crate::entities::{EntityID, NamedEntity};
 use crate::entities::animation::{AnimStep, EntityTimer};
 use crate::entities::player::{Dash, Player, PlayerSize, Transformed};
 use crate::entities::player_sensor::PlayerEnteredSensorEvent;
-use crate::logic::{Cutscene, Flags, GameData, Vanish};
+use crate::logic::{Cutscene, Flags, GameData, Pogo, Vanish};
 use crate::music::{PlaySFXEvent, SFX};
 use crate::params;
 
@@ -40,7 +40,7 @@ pub fn move_player(
     mut query: Query<(
         Entity, &mut AnimStep, &mut Dash, &EntityID, &EntityTimer,
         &mut KinematicCharacterController, &mut TextureAtlasSprite,
-        Option<&KinematicCharacterControllerOutput>,
+        Option<&KinematicCharacterControllerOutput>, Option<&Pogo>,
     ), With<Player>>,
     data: Res<GameData>,
     mut sfx: EventWriter<PlaySFXEvent>,
@@ -48,7 +48,7 @@ pub fn move_player(
     let Ok((
                e, mut step, mut dash, id, timer,
                mut controller, mut sprite,
-               output,
+               output, pogo,
            )) = query.get_single_mut() else { return };
 
     let EntityID::Player(size) = id else { return };
@@ -152,7 +152,7 @@ pub fn move_player(
 
         //info!("{dy}");
 
-        let mid_jump_stop = !input.pressed(KeyCode::Space) && t_jump > params::JUMP_MIN;
+        let mid_jump_stop = !input.pressed(KeyCode::Space) && t_jump > params::JUMP_MIN && pogo.is_none();
         let landed = grounded && t_jump > params::JUMP_MIN;
 
         if dy <= 0. || mid_jump_stop || landed {
@@ -177,6 +177,10 @@ pub fn move_player(
         let dy = -g * delta * (t_fall + delta / 2.);
         translation.y += dy;
     }
+    if *step != AnimStep::Jump && pogo.is_some() {
+        commands.entity(e).remove::<Pogo>();
+    }
+
     // info!("{translation}");
     controller.translation = Some(translation);
 }
\ No newline at end of file
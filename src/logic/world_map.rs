@@ -0,0 +1,283 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{GameState, params};
+use crate::entities::checkpoint::AtCheckpoint;
+use crate::entities::player::Player;
+use crate::graphics::TextStyles;
+use crate::logic::{CSEvent, Cutscene, GameData, LevelManager, MapMarker};
+use crate::screens::Fonts;
+
+pub struct WorldMapPlugin;
+
+impl Plugin for WorldMapPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, (record_visited, toggle_map).run_if(in_state(GameState::Game)))
+            .add_systems(Update, update_map.after(toggle_map).run_if(resource_exists::<WorldMap>()))
+            .add_systems(OnExit(GameState::Game), close_map)
+        ;
+    }
+}
+
+/// Opened world map, the game is paused meanwhile.
+///
+/// Arrows pan the map, the jump key zooms in and the attack key zooms out.
+/// Fast travel only starts from a checkpoint: when the map is opened while standing in an
+/// unlocked one, left and right pick another one instead and the jump key warps the player there.
+#[derive(Resource)]
+pub struct WorldMap {
+    /// World position shown at the center of the screen
+    center: Vec2,
+    /// Map pixels per world pixel
+    zoom: f32,
+    /// Fast travel destination, index in [GameData::checkpoints]
+    travel: Option<usize>,
+}
+
+#[derive(Component)]
+struct MapRoot;
+
+/// Outline of a level, in world coordinates
+#[derive(Component)]
+struct MapCell(Rect);
+
+/// Marker or player dot, in world coordinates
+#[derive(Component)]
+struct MapDot(Vec2);
+
+fn close(commands: &mut Commands, roots: &Query<Entity, With<MapRoot>>, time: &mut Time) {
+    roots.for_each(|e| commands.entity(e).despawn_recursive());
+    commands.remove_resource::<WorldMap>();
+    time.unpause();
+}
+
+fn record_visited(
+    player: Query<&Transform, With<Player>>,
+    level_manager: Res<LevelManager>,
+    mut data: ResMut<GameData>,
+) {
+    let Ok(pos) = player.get_single() else { return };
+    let Some(level) = level_manager.level_at(pos.translation.truncate()) else { return };
+    if !data.visited.contains(level.iid()) {
+        data.visited.insert(level.iid().clone());
+    }
+}
+
+fn toggle_map(
+    mut commands: Commands,
+    input: Res<Input<KeyCode>>,
+    map: Option<Res<WorldMap>>,
+    cutscene: Option<Res<Cutscene>>,
+    at_checkpoint: Option<Res<AtCheckpoint>>,
+    player: Query<&Transform, With<Player>>,
+    level_manager: Res<LevelManager>,
+    data: Res<GameData>,
+    fonts: Res<Fonts>,
+    roots: Query<Entity, With<MapRoot>>,
+    mut time: ResMut<Time>,
+) {
+    if map.is_some() {
+        if input.just_pressed(KeyCode::M) || input.just_pressed(KeyCode::Escape) {
+            close(&mut commands, &roots, &mut time);
+        }
+        return;
+    }
+
+    if !input.just_pressed(KeyCode::M) || cutscene.is_some() { return; }
+    let Ok(player) = player.get_single() else { return };
+
+    // Fast travel needs another checkpoint to go to
+    let travel = at_checkpoint
+        .and_then(|at| data.checkpoints.iter().position(|iid| *iid == at.0))
+        .filter(|_| data.checkpoints.len() > 1);
+
+    commands.insert_resource(WorldMap { center: player.translation.truncate(), zoom: params::MAP_ZOOM, travel });
+    time.pause();
+
+    let dot = |color: Color| NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            width: Val::Px(params::MAP_MARKER_SIZE),
+            height: Val::Px(params::MAP_MARKER_SIZE),
+            ..default()
+        },
+        background_color: BackgroundColor(color),
+        z_index: ZIndex::Global(params::ui_z::MAP),
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.85)),
+            z_index: ZIndex::Global(params::ui_z::MAP),
+            ..default()
+        })
+        .insert(MapRoot)
+        .with_children(|builder| {
+            for level in level_manager.levels() {
+                let visited = data.visited.contains(level.iid());
+                builder
+                    .spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            border: UiRect::all(Val::Px(1.0)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(
+                            if visited { Color::rgb(0.3, 0.3, 0.4) } else { Color::NONE }
+                        ),
+                        border_color: BorderColor(
+                            if visited { Color::rgb(0.8, 0.8, 0.9) } else { Color::rgb(0.3, 0.3, 0.35) }
+                        ),
+                        z_index: ZIndex::Global(params::ui_z::MAP),
+                        ..default()
+                    })
+                    .insert(MapCell(level.rect()))
+                ;
+
+                // Points of interest are only known once the level is visited
+                if !visited { continue }
+                for (marker, pos) in level.markers() {
+                    let color = match marker {
+                        MapMarker::Checkpoint(iid) if data.checkpoints.contains(iid) => Color::rgb(1.0, 0.6, 0.2),
+                        MapMarker::Checkpoint(_) => Color::rgb(0.4, 0.25, 0.15),
+                        MapMarker::Boss => Color::rgb(0.957, 0.137, 0.208),
+                    };
+                    builder.spawn(dot(color)).insert(MapDot(*pos));
+                }
+            }
+
+            builder
+                .spawn(dot(Color::WHITE))
+                .insert(MapDot(player.translation.truncate()))
+            ;
+
+            // Fast travel only starts from a checkpoint, tell the player once there is somewhere to go
+            let label = match travel {
+                Some(_) => "< Fast travel >",
+                None if data.checkpoints.len() > 1 => "Fast travel from a checkpoint",
+                None => return,
+            };
+
+            // The selected destination is kept at the center
+            let size = 2.0 * params::MAP_MARKER_SIZE;
+            if travel.is_some() {
+                builder.spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(params::HALF_WIDTH * params::SCALE - size / 2.),
+                        top: Val::Px(params::HALF_HEIGHT * params::SCALE - size / 2.),
+                        width: Val::Px(size),
+                        height: Val::Px(size),
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::WHITE),
+                    z_index: ZIndex::Global(params::ui_z::MAP),
+                    ..default()
+                });
+            }
+            builder.spawn(TextBundle {
+                text: Text::from_section(label, TextStyles::Basic.style(&fonts)).with_alignment(TextAlignment::Center),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(params::TOAST_TOP),
+                    width: Val::Percent(100.0),
+                    ..default()
+                },
+                z_index: ZIndex::Global(params::ui_z::MAP),
+                ..default()
+            });
+        })
+    ;
+}
+
+fn update_map(
+    mut commands: Commands,
+    mut map: ResMut<WorldMap>,
+    input: Res<Input<KeyCode>>,
+    data: Res<GameData>,
+    level_manager: Res<LevelManager>,
+    roots: Query<Entity, With<MapRoot>>,
+    mut cells: Query<(&MapCell, &mut Style), Without<MapDot>>,
+    mut dots: Query<(&MapDot, &mut Style), Without<MapCell>>,
+    mut time: ResMut<Time>,
+) {
+    // The game time is paused
+    let dt = time.raw_delta_seconds();
+
+    if let Some(selected) = map.travel {
+        let count = data.checkpoints.len();
+        let selected =
+            if input.just_pressed(KeyCode::Left) { (selected + count - 1) % count }
+            else if input.just_pressed(KeyCode::Right) { (selected + 1) % count }
+            else { selected };
+        map.travel = Some(selected);
+
+        let iid = &data.checkpoints[selected];
+        if let Some(pos) = level_manager.checkpoint_pos(iid) { map.center = pos; }
+
+        if input.just_pressed(KeyCode::Space) {
+            if let Some(id) = level_manager.spawner_id_of(iid) {
+                if id != level_manager.spawner_id() {
+                    commands.insert_resource(Cutscene::from(&VecDeque::from([
+                        CSEvent::fade_out(),
+                        CSEvent::Teleport(id.clone()),
+                        CSEvent::fade_in(),
+                    ])));
+                }
+            }
+            close(&mut commands, &roots, &mut time);
+            return;
+        }
+    } else {
+        let mut pan = Vec2::ZERO;
+        if input.pressed(KeyCode::Left) { pan.x -= 1.0; }
+        if input.pressed(KeyCode::Right) { pan.x += 1.0; }
+        if input.pressed(KeyCode::Up) { pan.y += 1.0; }
+        if input.pressed(KeyCode::Down) { pan.y -= 1.0; }
+        map.center += pan * params::MAP_PAN_SPEED * dt / map.zoom;
+
+        let (min_zoom, max_zoom) = params::MAP_ZOOM_RANGE;
+        if input.pressed(KeyCode::Space) { map.zoom = (map.zoom * params::MAP_ZOOM_SPEED.powf(dt)).min(max_zoom); }
+        if input.pressed(KeyCode::C) { map.zoom = (map.zoom / params::MAP_ZOOM_SPEED.powf(dt)).max(min_zoom); }
+    }
+
+    // UI coordinates go down
+    let half_screen = Vec2::new(params::HALF_WIDTH, params::HALF_HEIGHT) * params::SCALE;
+    let to_screen = |pos: Vec2| Vec2::new(
+        half_screen.x + (pos.x - map.center.x) * map.zoom,
+        half_screen.y - (pos.y - map.center.y) * map.zoom,
+    );
+
+    for (MapCell(rect), mut style) in cells.iter_mut() {
+        let top_left = to_screen(Vec2::new(rect.min.x, rect.max.y));
+        style.left = Val::Px(top_left.x);
+        style.top = Val::Px(top_left.y);
+        style.width = Val::Px(rect.width() * map.zoom);
+        style.height = Val::Px(rect.height() * map.zoom);
+    }
+
+    for (MapDot(pos), mut style) in dots.iter_mut() {
+        let center = to_screen(*pos);
+        style.left = Val::Px(center.x - params::MAP_MARKER_SIZE / 2.);
+        style.top = Val::Px(center.y - params::MAP_MARKER_SIZE / 2.);
+    }
+}
+
+fn close_map(
+    mut commands: Commands,
+    roots: Query<Entity, With<MapRoot>>,
+    mut time: ResMut<Time>,
+) {
+    close(&mut commands, &roots, &mut time);
+}
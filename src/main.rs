@@ -10,7 +10,7 @@ use crate::graphics::GraphicsPlugin;
 use crate::logic::LogicPlugin;
 use crate::music::{AudioPlugin, BGM};
 use crate::params::{HALF_HEIGHT, HALF_WIDTH, HEIGHT, SCALE, WIDTH};
-use crate::screens::ScreensPlugin;
+use crate::screens::{CameraController, ScreensPlugin};
 
 mod params;
 
@@ -27,6 +27,7 @@ mod definitions;
 pub enum GameState {
     #[default]
     Loading,
+    Menu,
     Game,
 }
 
@@ -103,6 +104,7 @@ fn init(mut commands: Commands) {
             },
             ..default()
         })
+        .insert(CameraController::default())
     ;
 }
 
@@ -16,14 +16,26 @@ pub mod z_pos {
     pub const PARTICLES: f32 = 60.0;
     pub const GUI: f32 = 100.;
     pub const IMAGE_ENTITY: f32 = 5.;
+    pub const PICKUP: f32 = 8.;
+    pub const PROJECTILE: f32 = 12.;
+    /// Between the background color of the levels and their image
+    pub const PARALLAX_BACK: f32 = 0.5;
+    pub const PARALLAX_FRONT: f32 = 50.;
+    pub const DARKNESS: f32 = 90.;
 }
 
 pub mod ui_z {
     pub const HEARTS: i32 = 80;
+    pub const BOSS_BAR: i32 = 80;
+    pub const MINIMAP: i32 = 80;
+    pub const TOAST: i32 = 85;
+    pub const MAP: i32 = 95;
     pub const CINEMA: i32 = 90;
     pub const FRAME: i32 = 100;
     pub const TEXT: i32 = 110;
     pub const TEXT2: i32 = 105;
+    /// Under the cutscene texts
+    pub const TRANSITION: i32 = 102;
 }
 
 pub struct SizeVal<T> where T: Copy {
@@ -73,11 +85,22 @@ pub const TRANSFORM_PARTICLES_TIMER: SizeVal<f32> = SizeVal::new(0.2, 0.1, 0.3);
 
 // --- Player
 pub const STARTING_LIFE: usize = 6;
+pub const HEART_LOSS_STEP: f32 = 0.2;
+pub const HEART_BLINK: f32 = 0.05;
 pub const ATTACK_STEPS: SizeVal<(f32, f32, f32, f32, f32)> = SizeVal::new(
     (0.15, 0.05, 0.05, 0.25, 0.2),
     (0.15, 0.05, 0.05, 0.25, 0.2),
     (0.15, 0.05, 0.2, 0.35, 0.2),
 );
+/// Damage of each swing of the ground combo
+pub const COMBO_DAMAGE: [usize; 3] = [1, 1, 2];
+pub const DIR_ATTACK_DAMAGE: usize = 1;
+/// Multiplies the damage of each swing
+pub const SWORD_DAMAGE: SizeVal<usize> = SizeVal::new(1, 1, 2);
+/// Multiplies the knockback of the enemies hit
+pub const SWORD_KNOCKBACK: SizeVal<f32> = SizeVal::new(1.0, 0.5, 2.0);
+/// Heavy hits can hurt armored entities
+pub const SWORD_HEAVY: SizeVal<bool> = SizeVal::new(false, false, true);
 pub const PLAYER_IDLE_INTERFRAME: f32 = 0.8;
 pub const PLAYER_WALK_INTERFRAME: f32 = 0.1;
 
@@ -86,13 +109,40 @@ pub const PLATFORM_UP_SPEED: f32 = 80.0;
 pub const PLATFORM_DOWN_SPEED: f32 = -100.0;
 pub const PLATFORM_DEAD_TIME: f32 = 0.35; // [up -> down] transition time when the player leaves
 
+// --- Checkpoints
+/// Size of the checkpoint's stone and flame
+pub const CHECKPOINT_BASE: (f32, f32) = (6.0, 4.0);
+pub const CHECKPOINT_FLAME: (f32, f32) = (2.0, 4.0);
+pub const CHECKPOINT_FLICKER_SPEED: f32 = 12.0;
+pub const CHECKPOINT_BURST_TIME: f32 = 0.15;
+
+// --- Toasts
+pub const TOAST_TOP: f32 = 48.0;
+pub const TOAST_TIME: f32 = 2.5;
+pub const TOAST_FADE: f32 = 0.5;
+
+// --- Breakable walls
+pub const BREAKABLE_WALL_HP: usize = 3;
+pub const WALL_HIT_SHAKE: f32 = 0.1;
+pub const WALL_BREAK_SHAKE: f32 = 0.4;
+pub const WALL_CRUMBLE_TIME: f32 = 0.15;
+
 // --- Enemies
+// In half hearts, a full heart so that resistances can absorb half of it
+pub const DEFAULT_CONTACT_DAMAGE: usize = 2;
+pub const PROJECTILE_DAMAGE: usize = 2;
+pub const HAZARD_DAMAGE: usize = 2;
+// Damage absorbed by the player for each damage type
+pub const CONTACT_RESISTANCE: SizeVal<usize> = SizeVal::new(0, 0, 1);
+pub const HAZARD_RESISTANCE: SizeVal<usize> = SizeVal::same(0);
+pub const PROJECTILE_RESISTANCE: SizeVal<usize> = SizeVal::new(0, 1, 0);
 pub const ENEMY_HURT_TIME: f32 = 0.25;
 pub const ENEMIES_KNOCKBACK_SPEED: f32 = 300.0;
 pub const ENEMIES_KNOCKBACK_TIME: f32 = 0.3;
 pub const SPIKES_KNOCKBACK_SPEED: f32 = 400.0;
 pub const SPIKES_KNOCKBACK_TIME: f32 = 0.5;
 pub const DEATH_SHAKE_TIME: f32 = 0.5;
+pub const DEATH_TRANSITION_TIME: f32 = 0.3;
 
 // --- Zombie
 pub const DEFAULT_ZOMBIE_SPEED: f32 = 0.35;
@@ -101,21 +151,66 @@ pub const ZOMBIE_AFRAID_SPEED_MUL: f32 = 3.0;
 pub const ZOMBIE_INITIAL_KNOCKBACK_SPEED: f32 = 8.0;
 pub const ZOMBIE_KNOCKBACK_TIME: f32 = 0.3;
 pub const ZOMBIE_HIT_STOP_DURATION: f32 = 0.15;
+pub const ZOMBIE_EYE_Y: f32 = 6.0;
+pub const ZOMBIE_AGGRO_RANGE: f32 = 72.0;
+pub const ZOMBIE_GIVE_UP_TIME: f32 = 2.0;
+pub const ZOMBIE_CHASE_SPEED_MUL: f32 = 2.0;
+pub const ZOMBIE_LUNGE_RANGE: f32 = 24.0;
+pub const ZOMBIE_LUNGE_WINDUP: f32 = 0.4;
+pub const ZOMBIE_LUNGE_TIME: f32 = 0.25;
+pub const ZOMBIE_LUNGE_SPEED_MUL: f32 = 6.0;
+pub const ZOMBIE_LUNGE_RECOVER: f32 = 0.6;
+pub const ZOMBIE_LEDGE_LOOKAHEAD: f32 = 6.0;
+pub const ZOMBIE_LEDGE_DEPTH: f32 = 14.0;
+pub const ZOMBIE_L_KNOCKBACK_RESISTANCE: f32 = 0.5;
+
+// --- Shooter
+pub const DEFAULT_SHOOTER_LIVES: usize = 2;
+pub const SHOOTER_RANGE: f32 = 128.0;
+pub const SHOOTER_COOLDOWN: f32 = 2.0;
+pub const SHOOTER_WINDUP: f32 = 0.4;
+pub const SHOOTER_MUZZLE_Y: f32 = 7.0;
+
+// --- Projectiles
+pub const PROJECTILE_SPEED: f32 = 90.0;
+pub const PROJECTILE_RADIUS: f32 = 1.5;
+pub const PROJECTILE_LIFETIME: f32 = 4.0;
+pub const PROJECTILE_DEFLECT_MUL: f32 = 1.5;
+pub const PROJECTILE_DEFLECT_DAMAGE: usize = 1;
 
 // --- Bosses
 pub const BOSS_EMITTER_DELAY: f32 = 0.15;
 pub const BOSS_EMITTER_ON: f32 = 0.045;
+pub const BOSS_KILL_HIT_STOP: f32 = 0.3;
+
+pub const BOSS_BAR_WIDTH: f32 = 384.0;
+pub const BOSS_BAR_HEIGHT: f32 = 12.0;
+pub const BOSS_BAR_BOTTOM: f32 = 32.0;
+/// Time before the damage trail starts to shrink
+pub const BOSS_BAR_TRAIL_DELAY: f32 = 0.4;
+/// Ratio of the bar drained per second by the damage trail
+pub const BOSS_BAR_TRAIL_SPEED: f32 = 0.5;
+
+// --- Boss rush
+pub const BOSS_RUSH_LIFE: usize = 10;
+/// Time between a boss death and the teleport to the next one
+pub const BOSS_RUSH_PAUSE: f32 = 2.0;
 
 // --- Boss 1
 pub const BOSS_STUN_DELAY: f32 = 20.0;
 pub const BOSS_EYES_Y: (f32, f32, f32) = (44.0, 52.0, 3.0);
 pub const BOSS_EYES_DX: f32 = 26.0;
 pub const BOSS1_EMITTER_OFFSET: (f32, f32) = (0.0, 42.0);
+pub const BOSS1_EYE_HP: usize = 2;
+pub const BOSS1_EYE_DETACH_SPEED: (f32, f32) = (30.0, 90.0);
+/// Rotation of the detached eyes, in radians per second
+pub const BOSS1_EYE_SPIN: f32 = 8.0;
 
 // --- Boss 2
 pub const BOSS2_SHAKE: f32 = 1.5;
 
 // --- Boss 3
+pub const BOSS3_TRANSFORM_TIME: f32 = 4.0;
 pub const BOSS3_GROUND: f32 = -66.0;
 pub const BOSS3_X: f32 = -1087.0;
 pub const BOSS3_LEVITATION_Y: f32 = 14.0;
@@ -129,10 +224,60 @@ pub const BOSS3_FALL_SPEED: f32 = 0.5;
 
 // --- Camera
 pub const CAM_Y_OFFSET: f32 = HEIGHT as f32 / 8.;
+/// How fast the camera catches up with its target, higher is snappier
+pub const CAM_SMOOTHING: f32 = 8.0;
+/// Distance shown ahead of the player, in world pixels
+pub const CAM_LOOK_AHEAD: f32 = 24.0;
+pub const CAM_DASH_LOOK_AHEAD: f32 = 48.0;
+pub const CAM_LOOK_AHEAD_SMOOTHING: f32 = 3.0;
+/// Camera magnification for each size, the view is wider when large
+pub const CAM_ZOOM: SizeVal<f32> = SizeVal::new(1.0, 1.25, 0.75);
+pub const CAM_ZOOM_SMOOTHING: f32 = 2.0;
 pub const SHAKE_STEP: f32 = 0.05;
 pub const SHAKE_RANGE: Range<f32> = 1.0..2.0;
 pub const SHAKE_LEN_S: f32 = SHAKE_STEP * 6.0;
 
+// --- World map
+/// Map pixels per world pixel when the map is opened
+pub const MAP_ZOOM: f32 = 0.25;
+pub const MAP_ZOOM_RANGE: (f32, f32) = (0.05, 1.0);
+/// Zoom factor applied per second while a zoom key is held
+pub const MAP_ZOOM_SPEED: f32 = 2.0;
+/// Screen pixels per second
+pub const MAP_PAN_SPEED: f32 = 320.0;
+pub const MAP_MARKER_SIZE: f32 = 6.0;
+
+// --- Minimap
+pub const MINIMAP_SIZE: (f32, f32) = (144.0, 96.0);
+pub const MINIMAP_MARGIN: f32 = 32.0;
+pub const MINIMAP_ZOOM: f32 = 0.15;
+/// Side of the explored squares, in world pixels
+pub const EXPLORE_CHUNK: f32 = 32.0;
+/// Chunks revealed around the player
+pub const EXPLORE_RADIUS: i32 = 3;
+
+// --- Ambient effects
+/// Distance above the view of the falling effects emitters
+pub const AMBIENT_MARGIN: f32 = 8.0;
+
+// --- Lighting
+/// Texels of the darkness overlay, stretched over the view
+pub const DARKNESS_RES: (usize, usize) = (WIDTH / 4, HEIGHT / 4);
+pub const PLAYER_LIGHT_RADIUS: SizeVal<f32> = SizeVal::new(56.0, 40.0, 80.0);
+/// Default radius of the LDtk lights
+pub const LIGHT_RADIUS: f32 = 48.0;
+pub const LIGHT_FLICKER_SPEED: f32 = 9.0;
+
+// --- Palette
+/// Colors per channel of the palette lookup textures
+pub const PALETTE_LUT_SIZE: usize = 16;
+pub const PALETTE_KEY: &str = "palette";
+/// Render layer of the quad showing the game, see [crate::graphics::PaletteSet]
+pub const SCREEN_LAYER: u8 = 1;
+
+// --- Transitions
+pub const TRANSITION_TIME: f32 = 0.5;
+
 // --- Cutscenes
 pub const TEXT_FADE_TIME: f32 = 0.4;
 pub const CHAR_DISPLAY_TIME: f32 = 0.08;
@@ -142,6 +287,7 @@ pub const INITIAL_SPAWNER_ID: &str = "start";
 
 // --- Flags
 pub const GAME_DATA_KEY: &str = "game_data";
+pub const BOSS_RUSH_RECORDS_KEY: &str = "boss_rush_records";
 
 // --- Music
 pub const BGM_VOLUME: f64 = 0.5;
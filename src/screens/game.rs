@@ -1,22 +1,35 @@
 use std::f32::consts::PI;
 
 use bevy::app::App;
-use bevy::math::{vec2, vec3};
+use bevy::math::vec2;
 use bevy::prelude::*;
 use bevy::transform::TransformSystem::TransformPropagate;
-use bevy_ecs_ldtk::LdtkWorldBundle;
+use bevy_ecs_ldtk::{EntityInstance, LdtkWorldBundle, Worldly};
+use bevy_ecs_ldtk::prelude::LdtkProject;
 use bevy_rapier2d::plugin::PhysicsSet;
 use rand::{Rng, thread_rng};
 
 use crate::{GameState, params};
+use crate::entities::EntityID;
+use crate::entities::animation::AnimStep;
+use crate::entities::boss::BossMinion;
+use crate::entities::camera_zone::CameraZone;
+use crate::entities::pickup::PickupItem;
 use crate::entities::player::Player;
+use crate::entities::projectile::Projectile;
+use crate::entities::spawner::SpawnersInit;
+use crate::graphics::{AmbientEmitter, lighting, parallax};
+use crate::graphics::lighting::Darkness;
+use crate::graphics::parallax::Parallax;
 use crate::graphics::ScreenTransition;
+use crate::logic::{Cutscene, LevelManager, Sword};
 use crate::screens::{Fonts, Textures};
 
 pub struct GamePlugin;
 
+/// Despawned when leaving the game, like the rest of the world
 #[derive(Component)]
-struct GameUI;
+pub struct GameUI;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
@@ -26,7 +39,8 @@ impl Plugin for GamePlugin {
                     update,
                 ).chain().run_if(in_state(GameState::Game))
             )
-            .add_systems(PostUpdate, (sync_camera).after(PhysicsSet::Writeback).before(TransformPropagate))
+            .add_systems(PostUpdate, (sync_camera, parallax::follow_camera, lighting::follow_darkness).chain().after(PhysicsSet::Writeback).before(TransformPropagate))
+            .add_systems(PostUpdate, lighting::draw_darkness.after(TransformPropagate))
             .add_systems(OnEnter(GameState::Game), enter)
             .add_systems(OnExit(GameState::Game), exit)
         ;
@@ -53,15 +67,30 @@ fn enter(
     });
 }
 
+/// Despawn the world, it is loaded again from the spawner of the [LevelManager] when coming back
 fn exit(
     mut commands: Commands,
-    to_clean: Query<Entity, With<GameUI>>,
+    to_clean: Query<Entity, Or<(
+        With<GameUI>,
+        With<Handle<LdtkProject>>,
+        With<Worldly>,
+        With<Player>,
+        With<Sword>,
+        With<PickupItem>,
+        With<Projectile>,
+        With<BossMinion>,
+        With<Parallax>,
+        With<AmbientEmitter>,
+        With<Darkness>,
+    )>>,
 ) {
     for id in to_clean.iter() {
         commands
             .entity(id)
             .despawn_recursive();
     }
+    commands.remove_resource::<SpawnersInit>();
+    commands.remove_resource::<Cutscene>();
 }
 
 #[derive(Resource)]
@@ -73,16 +102,89 @@ impl ScreenShake {
     }
 }
 
+/// Follows the player within the bounds of the current level, zooms with the player size
+#[derive(Component, Default)]
+pub struct CameraController {
+    /// Position of the camera without the shake
+    pos: Vec2,
+    look_ahead: f32,
+    /// Magnification, 1 is the pixel size of [params::SCALE]
+    zoom: f32,
+}
+
+/// Part of the distance to cover this frame when easing at the given rate
+fn smoothing(rate: f32, dt: f32) -> f32 {
+    1.0 - (-rate * dt).exp()
+}
+
+/// Keep the view inside the rect, centered on it when the view is larger
+fn clamp_view(rect: Rect, target: Vec2, half_view: Vec2) -> Vec2 {
+    let clamp = |target: f32, min: f32, max: f32, half: f32| {
+        if max - min <= 2. * half { (min + max) / 2. } else { target.clamp(min + half, max - half) }
+    };
+    Vec2::new(
+        clamp(target.x, rect.min.x, rect.max.x, half_view.x),
+        clamp(target.y, rect.min.y, rect.max.y, half_view.y),
+    )
+}
+
 fn sync_camera(
     mut commands: Commands,
-    player: Query<&Transform, With<Player>>,
-    mut camera: Query<&mut Transform, (With<Camera2d>, Without<Player>)>,
+    player: Query<(&Transform, &TextureAtlasSprite, &AnimStep, &EntityID), With<Player>>,
+    new_player: Query<(), Added<Player>>,
+    mut camera: Query<(&mut Transform, &mut CameraController), (With<Camera2d>, Without<Player>)>,
+    zones: Query<(&GlobalTransform, &EntityInstance), With<CameraZone>>,
+    level_manager: Res<LevelManager>,
     mut shake: Option<ResMut<ScreenShake>>,
     time: Res<Time>,
 ) {
-    let Some(player) = player.iter().next() else { return };
-    let Some(mut camera) = camera.iter_mut().next() else { return };
+    let Some((player, sprite, step, id)) = player.iter().next() else { return };
+    let Some((mut camera, mut controller)) = camera.iter_mut().next() else { return };
+    let player_pos = player.translation.truncate();
+    let dt = time.delta_seconds();
+    let respawned = !new_player.is_empty();
+    let level = level_manager.level_at(player_pos);
+
+    // Tight levels can keep the camera from zooming out
+    let EntityID::Player(size) = id else { return };
+    let zoom = params::CAM_ZOOM.get(size);
+    let zoom = level.map_or(zoom, |level| level.clamp_zoom(zoom));
+    if respawned || controller.zoom == 0. {
+        controller.zoom = zoom;
+    } else {
+        controller.zoom += (zoom - controller.zoom) * smoothing(params::CAM_ZOOM_SMOOTHING, dt);
+    }
+    let scale = 1. / (params::SCALE * controller.zoom);
+    camera.scale = Vec3::new(scale, scale, 1.);
+
+    // Show more of what's in front of the player, even more while dashing
+    let dir = if sprite.flip_x { -1. } else { 1. };
+    let look_ahead = dir * if *step == AnimStep::Dash { params::CAM_DASH_LOOK_AHEAD } else { params::CAM_LOOK_AHEAD };
+    controller.look_ahead += (look_ahead - controller.look_ahead) * smoothing(params::CAM_LOOK_AHEAD_SMOOTHING, dt);
 
+    // Visible area around the camera, in world pixels
+    let half_view = vec2(params::HALF_WIDTH, params::HALF_HEIGHT) * camera.scale.x * params::SCALE;
+
+    let zone = zones
+        .iter()
+        .map(|(transform, instance)| CameraZone::rect(transform, instance))
+        .find(|zone| zone.contains(player_pos));
+
+    let target = player_pos + vec2(controller.look_ahead, params::CAM_Y_OFFSET);
+    let target = match (zone, level) {
+        (Some(zone), _) => clamp_view(zone, target, half_view),
+        (None, Some(level)) => clamp_view(level.rect(), target, half_view),
+        (None, None) => target,
+    };
+
+    // Snap after a respawn, ease otherwise (also between levels)
+    if respawned {
+        controller.pos = target;
+    } else {
+        controller.pos += (target - controller.pos) * smoothing(params::CAM_SMOOTHING, dt);
+    }
+
+    let mut offset = Vec2::ZERO;
     if let Some(mut shake) = shake {
         let a = (shake.0 / params::SHAKE_STEP) as usize;
         shake.0 -= time.delta_seconds();
@@ -92,16 +194,11 @@ fn sync_camera(
                 let intensity = thread_rng().gen_range(params::SHAKE_RANGE);
                 shake.1 = vec2(angle.cos() * intensity, angle.sin() * intensity);
             }
-            camera.translation.x = player.translation.x + shake.1.x;
-            camera.translation.y = player.translation.y + params::CAM_Y_OFFSET + shake.1.y;
+            offset = shake.1;
         } else {
             commands.remove_resource::<ScreenShake>();
         }
-    } else {
-        camera.translation = vec3(
-            player.translation.x,
-            player.translation.y + params::CAM_Y_OFFSET,
-            0.
-        );
     }
-}
\ No newline at end of file
+
+    camera.translation = (controller.pos + offset).extend(0.);
+}
@@ -11,7 +11,7 @@ impl Plugin for LoadingPlugin {
         app
             .add_loading_state(
                 LoadingState::new(GameState::Loading)
-                    .continue_to_state(GameState::Game),
+                    .continue_to_state(GameState::Menu),
             )
             .add_collection_to_loading_state::<_, Textures>(GameState::Loading)
             .add_collection_to_loading_state::<_, Fonts>(GameState::Loading)
@@ -42,6 +42,10 @@ pub struct Textures {
     #[asset(path = "zombie_S.png")]
     pub zombie_s: Handle<TextureAtlas>,
 
+    #[asset(texture_atlas(tile_size_x = 16., tile_size_y = 16., columns = 8, rows = 1, padding_x = 0., padding_y = 0.))]
+    #[asset(path = "shooter.png")]
+    pub shooter: Handle<TextureAtlas>,
+
     #[asset(texture_atlas(tile_size_x = 17., tile_size_y = 20., columns = 2, rows = 1, padding_x = 0., padding_y = 0.))]
     #[asset(path = "zombie_2_L.png")]
     pub zombie_2_l: Handle<TextureAtlas>,
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+use crate::definitions::bosses;
+use crate::graphics::{PaletteSet, ScreenTransition, TextStyles};
+use crate::logic::{BossRush, BossRushRecords, format_time, PlayerLife};
+use crate::screens::Fonts;
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(GameState::Menu), enter)
+            .add_systems(Update, update.run_if(in_state(GameState::Menu)))
+            .add_systems(OnExit(GameState::Menu), exit)
+        ;
+    }
+}
+
+#[derive(Component)]
+struct MenuUI;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum MenuChoice {
+    Story,
+    BossRush,
+    Palette,
+}
+
+impl MenuChoice {
+    fn label(&self, palette: PaletteSet) -> String {
+        match self {
+            MenuChoice::Story => "Story".to_string(),
+            MenuChoice::BossRush => "Boss rush".to_string(),
+            MenuChoice::Palette => format!("Colors: {}", palette.label()),
+        }
+    }
+}
+
+const CHOICES: [MenuChoice; 3] = [MenuChoice::Story, MenuChoice::BossRush, MenuChoice::Palette];
+
+#[derive(Component)]
+struct MenuEntry(usize);
+
+#[derive(Resource, Default)]
+struct MenuSelection(usize);
+
+fn enter(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    records: Res<BossRushRecords>,
+    palette: Option<Res<PaletteSet>>,
+) {
+    let palette = palette.map_or(PaletteSet::default(), |palette| *palette);
+    commands.init_resource::<MenuSelection>();
+
+    let best = bosses::BOSS_RUSH
+        .iter()
+        .enumerate()
+        .map(|(i, (boss, _))| format!("{}: {}", boss.name, format_time(records.best.get(i).copied().flatten())))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::BLACK),
+            ..default()
+        })
+        .insert(MenuUI)
+        .with_children(|builder| {
+            builder.spawn(TextBundle::from_section("TOTENINSEL", TextStyles::Basic.style(&fonts)));
+            for (i, choice) in CHOICES.iter().enumerate() {
+                builder
+                    .spawn(TextBundle::from_section(choice.label(palette), TextStyles::Basic.style(&fonts)))
+                    .insert(MenuEntry(i))
+                ;
+            }
+            builder.spawn(
+                TextBundle::from_section(best, TextStyles::Basic.style_with_alpha(&fonts, 0.5))
+                    .with_text_alignment(TextAlignment::Center)
+            );
+        })
+    ;
+}
+
+fn update(
+    mut commands: Commands,
+    input: Res<Input<KeyCode>>,
+    mut selection: ResMut<MenuSelection>,
+    mut entries: Query<(&MenuEntry, &mut Text)>,
+    mut player_life: ResMut<PlayerLife>,
+    mut transition: ResMut<ScreenTransition>,
+    palette: Option<ResMut<PaletteSet>>,
+) {
+    if input.just_pressed(KeyCode::Up) { selection.0 = selection.0.saturating_sub(1); }
+    if input.just_pressed(KeyCode::Down) { selection.0 = (selection.0 + 1).min(CHOICES.len() - 1); }
+
+    let confirm = input.just_pressed(KeyCode::Space) || input.just_pressed(KeyCode::C);
+
+    // Options change in place, the palette is only missing before the screen is set up
+    let mut palette_set = palette.as_deref().copied().unwrap_or_default();
+    if CHOICES[selection.0] == MenuChoice::Palette {
+        if confirm || input.just_pressed(KeyCode::Right) || input.just_pressed(KeyCode::Left) {
+            palette_set = palette_set.next();
+            match palette {
+                Some(mut palette) => *palette = palette_set,
+                None => commands.insert_resource(palette_set),
+            }
+        }
+    }
+
+    for (MenuEntry(i), mut text) in entries.iter_mut() {
+        let label = CHOICES[*i].label(palette_set);
+        text.sections[0].value = if *i == selection.0 { format!("> {} <", label) } else { label };
+    }
+
+    if confirm && CHOICES[selection.0] != MenuChoice::Palette && transition.is_none() {
+        if CHOICES[selection.0] == MenuChoice::BossRush {
+            BossRush::start(&mut commands, &mut player_life);
+        }
+        *transition = ScreenTransition::to(GameState::Game);
+    }
+}
+
+fn exit(
+    mut commands: Commands,
+    to_clean: Query<Entity, With<MenuUI>>,
+) {
+    for id in to_clean.iter() {
+        commands
+            .entity(id)
+            .despawn_recursive();
+    }
+    commands.remove_resource::<MenuSelection>();
+}
@@ -1,15 +1,17 @@
 use bevy::app::App;
 use bevy::prelude::*;
 
-pub use game::ScreenShake;
+pub use game::{CameraController, GameUI, ScreenShake};
 pub use loading::Fonts;
 pub use loading::Sounds;
 pub use loading::Textures;
 
 use crate::screens::game::GamePlugin;
 use crate::screens::loading::LoadingPlugin;
+use crate::screens::menu::MenuPlugin;
 
 mod loading;
+mod menu;
 mod game;
 
 pub struct ScreensPlugin;
@@ -19,6 +21,7 @@ impl Plugin for ScreensPlugin {
         app
             .add_plugins((
                 LoadingPlugin,
+                MenuPlugin,
                 GamePlugin,
             ))
         ;
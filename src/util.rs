@@ -47,6 +47,18 @@ pub fn get_ldtk_field_string(fields: &Vec<FieldInstance>, name: &str) -> Option<
     return None
 }
 
+/// Values of an array of strings, without the null ones
+pub fn get_ldtk_field_strings(fields: &Vec<FieldInstance>, name: &str) -> Vec<String> {
+    for field in fields {
+        if field.identifier == name {
+            if let FieldValue::Strings(strings) = &field.value {
+                return strings.iter().flatten().cloned().collect();
+            }
+        }
+    }
+    return vec![]
+}
+
 pub fn get_ldtk_field_bool(fields: &Vec<FieldInstance>, name: &str) -> Option<bool> {
     for field in fields {
         if field.identifier == name {